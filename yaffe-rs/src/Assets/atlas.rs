@@ -4,6 +4,7 @@ use crate::pooled_cache::PooledCache;
 use crate::PhysicalRect;
 use speedy2d::image::ImageHandle;
 use std::convert::TryInto;
+use std::path::Path;
 use std::rc::Rc;
 
 macro_rules! read_type {
@@ -18,8 +19,8 @@ macro_rules! read_type {
 pub fn load_texture_atlas<F>(
     map: &mut PooledCache<32, AssetKey, AssetSlot>,
     image: Rc<ImageHandle>,
-    path: &str,
-    image_path: &str,
+    path: &Path,
+    image_path: &Path,
     image_map: F,
 ) where
     F: Fn(&str) -> Images,
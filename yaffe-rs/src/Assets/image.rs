@@ -4,6 +4,7 @@ use crate::logger::{info, warn, PanicLogEntry};
 use crate::pooled_cache::PooledCache;
 use crate::{PhysicalRect, PhysicalSize};
 use speedy2d::{image::*, Graphics2D};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::Ordering;
 use std::time::Instant;
@@ -108,7 +109,7 @@ pub fn load_image_async(key: &AssetKey, path: std::path::PathBuf) -> Option<(Vec
 
 pub fn preload_image(
     graphics: &mut Graphics2D,
-    path: &'static str,
+    path: &Path,
     image_name: Images,
     map: &mut PooledCache<32, AssetKey, AssetSlot>,
 ) {
@@ -41,7 +41,7 @@ impl From<PathType> for AssetKey {
         match other {
             yaffe_lib::PathType::Url(s) => AssetKey::Url(s),
             yaffe_lib::PathType::File(s) => {
-                let canon = std::fs::canonicalize(Path::new("./plugins").join(s)).unwrap();
+                let canon = std::fs::canonicalize(crate::paths::plugins().join(s)).unwrap();
                 AssetKey::File(canon)
             }
         }
@@ -80,24 +80,24 @@ impl AssetSlot {
         }
     }
 
-    pub fn preloaded(path: &str, image: YaffeTexture) -> AssetSlot {
+    pub fn preloaded(path: &Path, image: YaffeTexture) -> AssetSlot {
         let size = image.size();
         AssetSlot {
             state: AtomicU8::new(ASSET_STATE_LOADED),
-            path: Path::new(path).to_path_buf(),
+            path: path.to_path_buf(),
             data: AssetData::Image(image),
             data_length: (size.x * size.y * 4.) as usize,
             last_request: Instant::now(),
         }
     }
 
-    pub fn font(path: &str) -> AssetSlot {
+    pub fn font(path: &Path) -> AssetSlot {
         let data = std::fs::read(path).log_and_panic();
         let font = speedy2d::font::Font::new(&data).log_and_panic();
 
         AssetSlot {
             state: AtomicU8::new(ASSET_STATE_LOADED),
-            path: Path::new(path).to_path_buf(),
+            path: path.to_path_buf(),
             data: AssetData::Font(font),
             data_length: data.len(),
             last_request: Instant::now(),
@@ -115,10 +115,11 @@ pub fn preload_assets(graphics: &mut Graphics) {
     //TODO this sucks
     let g = unsafe { &mut *graphics.graphics_ptr };
     let mut map = graphics.asset_cache.borrow_mut();
-    let data = g.create_image_from_file_path(None, ImageSmoothingMode::Linear, "./Assets/packed.png").log_and_panic();
+    let packed = crate::paths::app_asset("packed.png");
+    let data = g.create_image_from_file_path(None, ImageSmoothingMode::Linear, &packed).log_and_panic();
     let image = Rc::new(data);
 
-    load_texture_atlas(&mut map, image, "./Assets/atlas.tex", "./Assets/packed.png", |image| match image {
+    load_texture_atlas(&mut map, image, &crate::paths::app_asset("atlas.tex"), &packed, |image| match image {
         "error.png" => Images::Error,
         "question.png" => Images::Question,
         "arrow_up.png" => Images::ArrowUp,
@@ -140,14 +141,12 @@ pub fn preload_assets(graphics: &mut Graphics) {
         _ => panic!("Unknown image found in texture atlas"),
     });
 
-    preload_image(g, "./Assets/placeholder.jpg", Images::Placeholder, &mut map);
+    preload_image(g, &crate::paths::app_asset("placeholder.jpg"), Images::Placeholder, &mut map);
 
-    map.insert(
-        AssetKey::image(Images::Background),
-        AssetSlot::new(Path::new(r"./Assets/background.jpg").to_path_buf()),
-    );
+    map.insert(AssetKey::image(Images::Background), AssetSlot::new(crate::paths::app_asset("background.jpg")));
 
-    map.insert(AssetKey::Static(AssetTypes::Font(Fonts::Regular)), AssetSlot::font("./Assets/Roboto-Regular.ttf"));
+    let font = crate::paths::app_asset("Roboto-Regular.ttf");
+    map.insert(AssetKey::Static(AssetTypes::Font(Fonts::Regular)), AssetSlot::font(&font));
 }
 
 pub fn get_asset_slot<'a>(map: &'a mut PooledCache<32, AssetKey, AssetSlot>, asset: &AssetKey) -> &'a mut AssetSlot {
@@ -165,7 +164,7 @@ fn asset_path_is_valid(asset: &AssetKey, path: &Path) -> bool {
 }
 
 pub fn get_asset_path(platform: &str, name: &str) -> PathBuf {
-    let platform = Path::new("./Assets").join(crate::os::sanitize_file(platform));
+    let platform = crate::paths::artwork().join(crate::os::sanitize_file(platform));
     let name = crate::os::sanitize_file(name);
     let name = format!("{name}.jpg");
     platform.join(name)
@@ -26,6 +26,8 @@ impl<L: ListItem> List<L> {
 
     pub fn get_selected(&self) -> &L { &self.items[self.index] }
 
+    pub fn get_selected_index(&self) -> usize { self.index }
+
     fn move_index(&mut self, new_index: usize, animations: &mut AnimationManager) {
         self.index = new_index;
 
@@ -1,9 +1,13 @@
 use crate::input::InputType;
 use crate::ui::{AnimationManager, DeferredAction, LayoutElement, UiElement, ValueElement, WidgetId};
 use crate::{Actions, Graphics, LogicalSize};
-use std::hash::{Hash, Hasher};
+use sha1::Sha1;
 
 const PIN_SIZE: usize = 8;
+/// Passcodes are short so hashing is made slow to make guessing them from the database impractical
+/// The rounds are saved with the hash so this can be raised without breaking existing passcodes
+const HASH_ROUNDS: u32 = 100_000;
+const HASH_PREFIX: &str = "pbkdf2$";
 
 #[derive(Default, Copy, Clone)]
 pub struct RestrictedPasscode {
@@ -18,6 +22,26 @@ impl RestrictedPasscode {
         }
     }
     pub fn len(&self) -> usize { self.length }
+
+    /// Hashes the passcode with a salt so it can be saved without storing the passcode itself
+    pub fn hash(&self, salt: &str) -> String {
+        format!("{HASH_PREFIX}{HASH_ROUNDS}${}", self.derive_key(salt, HASH_ROUNDS))
+    }
+
+    /// Checks the passcode against a hash that was saved by `hash`
+    pub fn verify(&self, salt: &str, hash: &str) -> bool {
+        match hash.strip_prefix(HASH_PREFIX).and_then(|h| h.split_once('$')) {
+            Some((rounds, key)) => rounds.parse().is_ok_and(|rounds| self.derive_key(salt, rounds) == key),
+            None => false,
+        }
+    }
+
+    fn derive_key(&self, salt: &str, rounds: u32) -> String {
+        let code: String = self.code[..self.length].iter().collect();
+        let mut key = [0u8; 20];
+        pbkdf2::pbkdf2_hmac::<Sha1>(code.as_bytes(), salt.as_bytes(), rounds, &mut key);
+        key.iter().map(|b| format!("{b:02x}")).collect()
+    }
 }
 impl PartialEq for RestrictedPasscode {
     fn eq(&self, other: &Self) -> bool {
@@ -76,8 +100,20 @@ impl ValueElement<RestrictedPasscode> for PassBox {
     fn value(&self) -> RestrictedPasscode { self.passcode }
 }
 
+/// Passcodes are saved so every action needs to always map to the same character
 fn action_to_char(action: &Actions) -> char {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    action.hash(&mut hasher);
-    hasher.finish() as u8 as char
+    match action {
+        Actions::Info => 'i',
+        Actions::Accept => 'a',
+        Actions::Back => 'b',
+        Actions::Up => 'u',
+        Actions::Down => 'd',
+        Actions::Left => 'l',
+        Actions::Right => 'r',
+        Actions::Filter => 'f',
+        Actions::ToggleFavorite => 'v',
+        Actions::ToggleOverlay => 'o',
+        Actions::ShowMenu => 'm',
+        Actions::KeyPress(_) => 'k',
+    }
 }
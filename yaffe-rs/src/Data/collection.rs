@@ -0,0 +1,107 @@
+use super::{execute_select, execute_select_once, execute_update, GameInfo, ProfileInfo, QueryResult, YaffeConnection};
+use crate::{create_statement, get_column, Tile};
+
+crate::table_struct!(
+    pub struct CollectionInfo {
+        pub id: i64,
+        pub name: String,
+    }
+);
+
+impl CollectionInfo {
+    /// Gets all collections ordered by name
+    pub fn get_all() -> QueryResult<Vec<CollectionInfo>> {
+        const QS_GET_ALL_COLLECTIONS: &str = "SELECT id, name FROM Collections ORDER BY name";
+        crate::logger::info!("Getting all collections");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_ALL_COLLECTIONS,)?;
+
+        let mut result = vec![];
+        execute_select(stmt, |r| {
+            let id = get_column!(r, i64, "id");
+            let name = get_column!(r, String, "name");
+            result.push(CollectionInfo { id, name });
+        })?;
+
+        Ok(result)
+    }
+
+    /// Adds a new collection, returning its id
+    pub fn insert(name: &str) -> QueryResult<i64> {
+        const QS_GET_NEXT_ID: &str = "SELECT COALESCE(MAX(id), 0) + 1 FROM Collections";
+        const QS_ADD_COLLECTION: &str = "INSERT INTO Collections (id, name) VALUES (@CollectionId, @Name)";
+        crate::logger::info!("Inserting new collection into database {name}");
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_NEXT_ID,)?;
+        execute_select_once(&mut stmt)?;
+        let id = get_column!(stmt, i64, 0);
+
+        let stmt = create_statement!(con, QS_ADD_COLLECTION, id, name)?;
+        execute_update(stmt)?;
+        Ok(id)
+    }
+
+    /// Changes the name of a collection
+    pub fn rename(id: i64, name: &str) -> QueryResult<()> {
+        const QS_RENAME_COLLECTION: &str = "UPDATE Collections SET name = @Name WHERE id = @CollectionId";
+        crate::logger::info!("Renaming collection {id} to {name}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_RENAME_COLLECTION, name, id)?;
+        execute_update(stmt)
+    }
+
+    /// Removes a collection, the games in it are not affected
+    pub fn delete(id: i64) -> QueryResult<()> {
+        const QS_DELETE_COLLECTION_GAMES: &str = "DELETE FROM CollectionGames WHERE collection = @CollectionId";
+        const QS_DELETE_COLLECTION: &str = "DELETE FROM Collections WHERE id = @CollectionId";
+        crate::logger::info!("Deleting collection {id}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_DELETE_COLLECTION_GAMES, id)?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(con, QS_DELETE_COLLECTION, id)?;
+        execute_update(stmt)
+    }
+
+    /// Adds a game to a collection if it isn't already part of it
+    pub fn add_game(id: i64, platform: i64, file: &str) -> QueryResult<()> {
+        const QS_GET_COLLECTION_GAME_EXISTS: &str = "SELECT COUNT(1) FROM CollectionGames WHERE collection = @CollectionId AND platform = @Platform AND filename = @Game";
+        const QS_ADD_COLLECTION_GAME: &str =
+            "INSERT INTO CollectionGames (collection, platform, filename) VALUES (@CollectionId, @Platform, @Game)";
+        crate::logger::info!("Adding {file} to collection {id}");
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_COLLECTION_GAME_EXISTS, id, platform, file)?;
+        execute_select_once(&mut stmt)?;
+        if get_column!(stmt, i64, 0) > 0 {
+            return Ok(());
+        }
+
+        let stmt = create_statement!(con, QS_ADD_COLLECTION_GAME, id, platform, file)?;
+        execute_update(stmt)
+    }
+
+    /// Removes a game from a collection
+    pub fn remove_game(id: i64, platform: i64, file: &str) -> QueryResult<()> {
+        const QS_REMOVE_COLLECTION_GAME: &str =
+            "DELETE FROM CollectionGames WHERE collection = @CollectionId AND platform = @Platform AND filename = @Game";
+        crate::logger::info!("Removing {file} from collection {id}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_REMOVE_COLLECTION_GAME, id, platform, file)?;
+        execute_update(stmt)
+    }
+
+    /// Gets all games in a collection, which can be from multiple platforms
+    pub fn get_games(id: i64) -> QueryResult<Vec<Tile>> {
+        const QS_GET_COLLECTION_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.filename, g.released, pg.favorite, pg.playtime, pg.launchcount, g.missing, p.id as platformid, p.platform FROM CollectionGames c JOIN Games g ON g.platform = c.platform AND g.filename = c.filename JOIN Platforms p ON g.platform = p.id LEFT JOIN ProfileGames pg ON pg.profile = @Profile AND pg.platform = g.platform AND pg.filename = g.filename WHERE c.collection = @CollectionId ORDER BY g.name";
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_COLLECTION_GAMES, ProfileInfo::current(), id)?;
+
+        GameInfo::read_tiles(stmt)
+    }
+}
@@ -0,0 +1,112 @@
+use super::{execute_update, QueryError, QueryResult};
+use crate::create_statement;
+use crate::logger::{LogEntry, PanicLogEntry};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Mutex;
+
+/// Enough idle connections for every job thread and the main thread
+const MAX_IDLE_CONNECTIONS: usize = 9;
+/// How long to wait for another connection to finish writing before giving up
+const BUSY_TIMEOUT_MS: usize = 10_000;
+
+static POOL: Mutex<Vec<sqlite::Connection>> = Mutex::new(Vec::new());
+
+thread_local! {
+    // Connection of the transaction open on this thread, if any
+    static TRANSACTION: RefCell<Option<sqlite::Connection>> = const { RefCell::new(None) };
+}
+
+/// A connection to the database that is returned to a shared pool when dropped
+/// If the current thread has a `Transaction` open, its connection is used instead
+pub struct YaffeConnection {
+    con: Option<sqlite::Connection>,
+    in_transaction: bool,
+}
+impl YaffeConnection {
+    pub fn new() -> QueryResult<YaffeConnection> {
+        if let Some(con) = TRANSACTION.with_borrow_mut(|t| t.take()) {
+            return Ok(YaffeConnection { con: Some(con), in_transaction: true });
+        }
+
+        let pooled = POOL.lock().log_and_panic().pop();
+        let con = match pooled {
+            Some(con) => con,
+            None => {
+                let mut con = sqlite::open(crate::paths::database())?;
+                con.set_busy_timeout(BUSY_TIMEOUT_MS).log("Unable to set database busy timeout");
+                con
+            }
+        };
+        Ok(YaffeConnection { con: Some(con), in_transaction: false })
+    }
+}
+impl Deref for YaffeConnection {
+    type Target = sqlite::Connection;
+
+    fn deref(&self) -> &sqlite::Connection { self.con.as_ref().unwrap() }
+}
+impl Drop for YaffeConnection {
+    fn drop(&mut self) {
+        let Some(con) = self.con.take() else {
+            return;
+        };
+
+        if self.in_transaction {
+            TRANSACTION.set(Some(con));
+        } else {
+            let mut pool = POOL.lock().log_and_panic();
+            if pool.len() < MAX_IDLE_CONNECTIONS {
+                pool.push(con);
+            }
+        }
+    }
+}
+
+/// Groups many updates in to one transaction, such as when scanning or importing games
+/// Every query made on this thread until the transaction is committed is part of it.
+/// Transactions that are dropped without being committed are rolled back
+pub struct Transaction {
+    finished: bool,
+    // The transaction belongs to the thread that started it
+    _thread: PhantomData<*const ()>,
+}
+impl Transaction {
+    pub fn begin() -> QueryResult<Transaction> {
+        let mut con = YaffeConnection::new()?;
+        if con.in_transaction {
+            return Err(QueryError::NestedTransaction);
+        }
+
+        execute_update(create_statement!(con, "BEGIN TRANSACTION",)?)?;
+        con.in_transaction = true;
+        Ok(Transaction { finished: false, _thread: PhantomData })
+    }
+
+    pub fn commit(mut self) -> QueryResult<()> {
+        let result = self.finish("COMMIT");
+        if result.is_err() {
+            // A failed commit leaves the transaction open
+            self.finish("ROLLBACK").log("Unable to roll back transaction");
+        }
+        result
+    }
+
+    fn finish(&mut self, sql: &str) -> QueryResult<()> {
+        self.finished = true;
+
+        let mut con = YaffeConnection::new()?;
+        let result = execute_update(create_statement!(con, sql,)?);
+        // Rolling back after a failed commit still needs the connection
+        con.in_transaction = result.is_err() && sql == "COMMIT";
+        result
+    }
+}
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finish("ROLLBACK").log("Unable to roll back transaction");
+        }
+    }
+}
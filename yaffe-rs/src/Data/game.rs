@@ -1,5 +1,8 @@
-use super::{execute_select, execute_select_once, execute_update, QueryError, QueryResult, YaffeConnection};
+use super::{
+    execute_select, execute_select_once, execute_update, ProfileInfo, QueryError, QueryResult, YaffeConnection,
+};
 use crate::create_statement;
+use crate::scraper::RomHashes;
 use crate::{get_column, Tile};
 
 crate::table_struct!(
@@ -13,6 +16,15 @@ crate::table_struct!(
         pub filename: String,
         pub platform: i64,
         pub lastrun: i64,
+        pub crc32: String,
+        pub md5: String,
+        pub sha1: String,
+        pub favorite: bool,
+        pub playtime: i64,
+        pub launchcount: i64,
+        pub path: String,
+        pub args: String,
+        pub missing: bool,
     }
 );
 impl GameInfo {
@@ -27,10 +39,45 @@ impl GameInfo {
         filename: String,
         platform: i64,
     ) -> GameInfo {
-        GameInfo { id, name, overview, players, filename, rating, released, platform, lastrun: 0 }
+        GameInfo {
+            id,
+            name,
+            overview,
+            players,
+            filename,
+            rating,
+            released,
+            platform,
+            lastrun: 0,
+            crc32: String::new(),
+            md5: String::new(),
+            sha1: String::new(),
+            favorite: false,
+            playtime: 0,
+            launchcount: 0,
+            path: String::new(),
+            args: String::new(),
+            missing: false,
+        }
+    }
+
+    /// Reads games that include their platform's id and name as tiles, for groups that span platforms
+    pub(super) fn read_tiles(stmt: sqlite::Statement) -> QueryResult<Vec<Tile>> {
+        let mut result = vec![];
+        execute_select(stmt, |r| {
+            let name = get_column!(r, String, "name");
+            let platform_name = get_column!(r, String, "platform");
+            let platform_id = get_column!(r, i64, "platformid");
+
+            let info = GameInfo::from_row(r, platform_id);
+            let boxart = crate::assets::get_asset_path(&platform_name, &name);
+            result.push(Tile::new_game(&info, platform_id, boxart));
+        })?;
+
+        Ok(result)
     }
 
-    fn from_row(row: &sqlite::Statement, platform: i64) -> GameInfo {
+    pub(super) fn from_row(row: &sqlite::Statement, platform: i64) -> GameInfo {
         let id = get_column!(row, i64, "id");
         let name = get_column!(row, String, "name");
         let overview = get_column!(row, String, "overview");
@@ -39,22 +86,68 @@ impl GameInfo {
         let released = get_column!(row, String, "released");
         let filename = get_column!(row, String, "filename");
         let lastrun = get_column!(row, i64, "lastrun");
+        let crc32 = get_column!(row, String, "crc32");
+        let md5 = get_column!(row, String, "md5");
+        let sha1 = get_column!(row, String, "sha1");
+        let favorite = get_column!(row, i64, "favorite") != 0;
+        let playtime = get_column!(row, i64, "playtime");
+        let launchcount = get_column!(row, i64, "launchcount");
+        let path = get_column!(row, String, "path");
+        let args = get_column!(row, String, "args");
+        let missing = get_column!(row, i64, "missing") != 0;
 
-        GameInfo { id, name, overview, players, filename, rating, released, platform, lastrun }
+        GameInfo {
+            id,
+            name,
+            overview,
+            players,
+            filename,
+            rating,
+            released,
+            platform,
+            lastrun,
+            crc32,
+            md5,
+            sha1,
+            favorite,
+            playtime,
+            launchcount,
+            path,
+            args,
+            missing,
+        }
     }
 
+    pub fn id(&self) -> i64 { self.id }
+
     pub fn platform(&self) -> i64 { self.platform }
 
-    pub fn get_all(platform: i64) -> Vec<GameInfo> {
-        const QS_GET_ALL_GAMES: &str = "SELECT id, name, overview, players, rating, released, filename, lastrun FROM Games WHERE platform = @Platform";
+    pub fn set_hashes(&mut self, hashes: &RomHashes) {
+        self.crc32 = hashes.crc32.clone();
+        self.md5 = hashes.md5.clone();
+        self.sha1 = hashes.sha1.clone();
+    }
+
+    pub fn get_all(platform: i64) -> QueryResult<Vec<GameInfo>> {
+        const QS_GET_ALL_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.released, g.filename, pg.lastrun, g.crc32, g.md5, g.sha1, pg.favorite, pg.playtime, pg.launchcount, g.path, g.args, g.missing FROM Games g LEFT JOIN ProfileGames pg ON pg.profile = @Profile AND pg.platform = g.platform AND pg.filename = g.filename WHERE g.platform = @Platform";
 
-        let con = YaffeConnection::new();
-        let stmt = create_statement!(con, QS_GET_ALL_GAMES, platform);
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_ALL_GAMES, ProfileInfo::current(), platform)?;
 
         let mut result = vec![];
-        execute_select(stmt, |r| result.push(GameInfo::from_row(r, platform)));
+        execute_select(stmt, |r| result.push(GameInfo::from_row(r, platform)))?;
 
-        result
+        Ok(result)
+    }
+
+    /// Gets a single game by its platform and file
+    pub fn get(platform: i64, file: &str) -> QueryResult<GameInfo> {
+        const QS_GET_GAME: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.released, g.filename, pg.lastrun, g.crc32, g.md5, g.sha1, pg.favorite, pg.playtime, pg.launchcount, g.path, g.args, g.missing FROM Games g LEFT JOIN ProfileGames pg ON pg.profile = @Profile AND pg.platform = g.platform AND pg.filename = g.filename WHERE g.platform = @Platform AND g.filename = @Game";
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_GAME, ProfileInfo::current(), platform, file)?;
+        execute_select_once(&mut stmt)?;
+        Ok(GameInfo::from_row(&stmt, platform))
     }
 
     /// Gets Name, Overview, Players, and Rating of a game
@@ -62,8 +155,8 @@ impl GameInfo {
         const QS_GET_GAME_EXISTS: &str = "SELECT COUNT(1) FROM Games WHERE platform = @Platform AND filename = @Game";
         crate::logger::info!("Getting all applications for {file}");
 
-        let con = YaffeConnection::new();
-        let mut stmt = create_statement!(con, QS_GET_GAME_EXISTS, id, file);
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_GAME_EXISTS, id, file)?;
 
         if execute_select_once(&mut stmt).is_ok() {
             let count = get_column!(stmt, i64, 0);
@@ -73,37 +166,167 @@ impl GameInfo {
         }
     }
 
-    /// Gets the most recent games launched from Yaffe
-    pub fn get_recent(max: i64) -> Vec<Tile> {
-        const QS_GET_RECENT_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.filename, g.released, p.id as platformid, p.platform FROM Games g, Platforms p WHERE g.platform = p.id AND lastrun IS NOT NULL ORDER BY lastrun DESC LIMIT @Max";
-        let con = YaffeConnection::new();
-        let stmt = create_statement!(con, QS_GET_RECENT_GAMES, max);
+    /// Finds a game on a platform whose rom matches any of the given hashes
+    pub fn find_by_hash(platform: i64, hashes: &RomHashes) -> QueryResult<GameInfo> {
+        const QS_GET_GAME_BY_HASH: &str = "
+        SELECT g.id, g.name, g.overview, g.players, g.rating, g.released, g.filename, pg.lastrun, g.crc32, g.md5, g.sha1, pg.favorite, pg.playtime, pg.launchcount, g.path, g.args, g.missing
+        FROM Games g
+        LEFT JOIN ProfileGames pg ON pg.profile = @Profile AND pg.platform = g.platform AND pg.filename = g.filename
+        WHERE g.platform = @Platform AND ((g.sha1 <> '' AND g.sha1 = @Sha1) OR (g.md5 <> '' AND g.md5 = @Md5) OR (g.crc32 <> '' AND g.crc32 = @Crc32))
+        ";
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(
+            con,
+            QS_GET_GAME_BY_HASH,
+            ProfileInfo::current(),
+            platform,
+            &*hashes.sha1,
+            &*hashes.md5,
+            &*hashes.crc32
+        )?;
+        execute_select_once(&mut stmt)?;
+        Ok(GameInfo::from_row(&stmt, platform))
+    }
+
+    /// Gets the file names of all games on a platform that have not been hashed
+    pub fn get_unhashed(platform: i64) -> QueryResult<Vec<String>> {
+        const QS_GET_UNHASHED_GAMES: &str =
+            "SELECT filename FROM Games WHERE platform = @Platform AND (sha1 IS NULL OR sha1 = '')";
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_UNHASHED_GAMES, platform)?;
 
         let mut result = vec![];
-        execute_select(stmt, |r| {
-            let name = get_column!(r, String, "name");
-            let platform_name = get_column!(r, String, "platform");
-            let platform_id = get_column!(r, i64, "platformid");
+        execute_select(stmt, |r| result.push(get_column!(r, String, "filename")))?;
+        Ok(result)
+    }
 
-            let info = GameInfo::from_row(r, platform_id);
-            let boxart = crate::assets::get_asset_path(&platform_name, &name);
-            result.push(Tile::new_game(&info, platform_id, boxart));
-        });
+    /// Updates the stored rom hashes for a game
+    pub fn update_hashes(platform: i64, file: &str, hashes: &RomHashes) -> QueryResult<()> {
+        const QS_UPDATE_GAME_HASHES: &str = "
+        UPDATE Games
+        SET crc32 = @Crc32, md5 = @Md5, sha1 = @Sha1
+        WHERE platform = @Platform AND filename = @Game
+        ";
+        crate::logger::info!("Updating hashes for game {file}");
+
+        let con = YaffeConnection::new()?;
+        let stmt =
+            create_statement!(con, QS_UPDATE_GAME_HASHES, &*hashes.crc32, &*hashes.md5, &*hashes.sha1, platform, file)?;
+
+        execute_update(stmt)
+    }
+
+    /// Gets the most recent games launched from Yaffe
+    pub fn get_recent(max: i64) -> QueryResult<Vec<Tile>> {
+        const QS_GET_RECENT_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.filename, g.released, pg.favorite, pg.playtime, pg.launchcount, g.missing, p.id as platformid, p.platform FROM ProfileGames pg, Games g, Platforms p WHERE pg.profile = @Profile AND g.platform = pg.platform AND g.filename = pg.filename AND g.platform = p.id AND pg.lastrun IS NOT NULL ORDER BY pg.lastrun DESC LIMIT @Max";
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_RECENT_GAMES, ProfileInfo::current(), max)?;
 
-        result
+        GameInfo::read_tiles(stmt)
+    }
+
+    /// Gets the games with the most play time across all platforms
+    pub fn get_most_played(max: i64) -> QueryResult<Vec<Tile>> {
+        const QS_GET_MOST_PLAYED_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.filename, g.released, pg.favorite, pg.playtime, pg.launchcount, g.missing, p.id as platformid, p.platform FROM ProfileGames pg, Games g, Platforms p WHERE pg.profile = @Profile AND g.platform = pg.platform AND g.filename = pg.filename AND g.platform = p.id AND pg.playtime > 0 ORDER BY pg.playtime DESC LIMIT @Max";
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_MOST_PLAYED_GAMES, ProfileInfo::current(), max)?;
+
+        GameInfo::read_tiles(stmt)
+    }
+
+    /// Gets every game marked as a favorite across all platforms
+    pub fn get_favorites() -> QueryResult<Vec<Tile>> {
+        const QS_GET_FAVORITE_GAMES: &str = "SELECT g.id, g.name, g.overview, g.players, g.rating, g.filename, g.released, pg.favorite, pg.playtime, pg.launchcount, g.missing, p.id as platformid, p.platform FROM ProfileGames pg, Games g, Platforms p WHERE pg.profile = @Profile AND g.platform = pg.platform AND g.filename = pg.filename AND g.platform = p.id AND pg.favorite = 1 ORDER BY g.name";
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_FAVORITE_GAMES, ProfileInfo::current())?;
+
+        GameInfo::read_tiles(stmt)
+    }
+
+    /// Marks or unmarks a game as a favorite
+    pub fn set_favorite(platform: i64, file: &str, favorite: bool) -> QueryResult<()> {
+        const QS_UPDATE_GAME_FAVORITE: &str = "
+        INSERT INTO ProfileGames (profile, platform, filename, favorite)
+        VALUES (@Profile, @Platform, @Game, @Favorite)
+        ON CONFLICT (profile, platform, filename) DO UPDATE SET favorite = excluded.favorite
+        ";
+        crate::logger::info!("Setting favorite for game {file} to {favorite}");
+
+        let con = YaffeConnection::new()?;
+        let stmt =
+            create_statement!(con, QS_UPDATE_GAME_FAVORITE, ProfileInfo::current(), platform, file, favorite as i64)?;
+
+        execute_update(stmt)
+    }
+
+    /// Marks whether the rom for a game can no longer be found
+    pub fn set_missing(platform: i64, file: &str, missing: bool) -> QueryResult<()> {
+        const QS_UPDATE_GAME_MISSING: &str = "
+        UPDATE Games
+        SET missing = @Missing
+        WHERE platform = @Platform AND filename = @Game
+        ";
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_GAME_MISSING, missing as i64, platform, file)?;
+
+        execute_update(stmt)
+    }
+
+    /// Removes a game, along with any collections it was part of and its history in every profile
+    pub fn delete(platform: i64, file: &str) -> QueryResult<()> {
+        const QS_DELETE_COLLECTION_GAMES: &str =
+            "DELETE FROM CollectionGames WHERE platform = @Platform AND filename = @Game";
+        const QS_DELETE_PROFILE_GAMES: &str =
+            "DELETE FROM ProfileGames WHERE platform = @Platform AND filename = @Game";
+        const QS_DELETE_GAME: &str = "DELETE FROM Games WHERE platform = @Platform AND filename = @Game";
+        crate::logger::info!("Deleting game {file}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_DELETE_COLLECTION_GAMES, platform, file)?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(con, QS_DELETE_PROFILE_GAMES, platform, file)?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(con, QS_DELETE_GAME, platform, file)?;
+        execute_update(stmt)
+    }
+
+    /// Points a game, its history, and any collections it is part of, to a different rom file
+    pub fn relink(platform: i64, file: &str, new_file: &str) -> QueryResult<()> {
+        const QS_RELINK_COLLECTION_GAMES: &str =
+            "UPDATE CollectionGames SET filename = @NewGame WHERE platform = @Platform AND filename = @Game";
+        const QS_RELINK_PROFILE_GAMES: &str =
+            "UPDATE ProfileGames SET filename = @NewGame WHERE platform = @Platform AND filename = @Game";
+        const QS_RELINK_GAME: &str =
+            "UPDATE Games SET filename = @NewGame, missing = 0 WHERE platform = @Platform AND filename = @Game";
+        crate::logger::info!("Relinking game {file} to {new_file}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_RELINK_COLLECTION_GAMES, new_file, platform, file)?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(con, QS_RELINK_PROFILE_GAMES, new_file, platform, file)?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(con, QS_RELINK_GAME, new_file, platform, file)?;
+        execute_update(stmt)
     }
 
     /// Adds a new game
     pub fn insert(game: &GameInfo) -> QueryResult<()> {
         const QS_ADD_GAME: &str = "
         INSERT INTO Games
-        (id, platform, name, overview, players, rating, filename)
+        (id, platform, name, overview, players, rating, released, filename, crc32, md5, sha1)
         VALUES
-        (@GameId, @Platform, @Name, @Overview, @Players, @Rating, @FileName)
+        (@GameId, @Platform, @Name, @Overview, @Players, @Rating, @Released, @FileName, @Crc32, @Md5, @Sha1)
         ";
         crate::logger::info!("Inserting new game into database {}", game.name);
 
-        let con = YaffeConnection::new();
+        let con = YaffeConnection::new()?;
         let stmt = create_statement!(
             con,
             QS_ADD_GAME,
@@ -113,23 +336,159 @@ impl GameInfo {
             &*game.overview,
             game.players,
             &*game.rating,
+            &*game.released,
+            &*game.filename,
+            &*game.crc32,
+            &*game.md5,
+            &*game.sha1
+        )?;
+
+        execute_update(stmt)
+    }
+
+    /// Updates the editable metadata of an existing game
+    pub fn update(game: &GameInfo) -> QueryResult<()> {
+        const QS_UPDATE_GAME: &str = "
+        UPDATE Games
+        SET name = @Name, overview = @Overview, players = @Players, rating = @Rating, released = @Released,
+            path = @Path, args = @Args
+        WHERE platform = @Platform AND filename = @Game
+        ";
+        crate::logger::info!("Updating game {}", game.name);
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(
+            con,
+            QS_UPDATE_GAME,
+            &*game.name,
+            &*game.overview,
+            game.players,
+            &*game.rating,
+            &*game.released,
+            &*game.path,
+            &*game.args,
+            game.platform,
             &*game.filename
-        );
+        )?;
 
         execute_update(stmt)
     }
 
-    /// Updates the last run value for a game
-    pub fn update_last_run(id: i64, file: &str) -> QueryResult<()> {
-        const QS_UPDATE_GAME_LAST_RUN: &str = "
+    /// Replaces the scraped information of an existing game, keeping when it was last run
+    pub fn replace(game: &GameInfo) -> QueryResult<()> {
+        const QS_REPLACE_GAME: &str = "
         UPDATE Games
-        SET lastrun = strftime('%s', 'now', 'localtime')
+        SET id = @GameId, name = @Name, overview = @Overview, players = @Players, rating = @Rating, released = @Released,
+            crc32 = @Crc32, md5 = @Md5, sha1 = @Sha1
         WHERE platform = @Platform AND filename = @Game
         ";
+        crate::logger::info!("Replacing game {}", game.name);
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(
+            con,
+            QS_REPLACE_GAME,
+            game.id,
+            &*game.name,
+            &*game.overview,
+            game.players,
+            &*game.rating,
+            &*game.released,
+            &*game.crc32,
+            &*game.md5,
+            &*game.sha1,
+            game.platform,
+            &*game.filename
+        )?;
+
+        execute_update(stmt)
+    }
+
+    /// Adds a game, or overwrites everything stored about it if it already exists
+    /// History such as play time is saved to the current profile
+    pub fn merge(game: &GameInfo) -> QueryResult<()> {
+        const QS_MERGE_GAME: &str = "
+        UPDATE Games
+        SET id = @GameId, name = @Name, overview = @Overview, players = @Players, rating = @Rating, released = @Released,
+            crc32 = @Crc32, md5 = @Md5, sha1 = @Sha1, path = @Path, args = @Args
+        WHERE platform = @Platform AND filename = @Game
+        ";
+        const QS_MERGE_PROFILE_GAME: &str = "
+        INSERT INTO ProfileGames (profile, platform, filename, favorite, playtime, launchcount, lastrun)
+        VALUES (@Profile, @Platform, @Game, @Favorite, @PlayTime, @LaunchCount, @LastRun)
+        ON CONFLICT (profile, platform, filename) DO UPDATE
+        SET favorite = excluded.favorite, playtime = excluded.playtime, launchcount = excluded.launchcount,
+            lastrun = excluded.lastrun
+        ";
+        if !GameInfo::exists(game.platform, &game.filename)? {
+            GameInfo::insert(game)?;
+        }
+        crate::logger::info!("Merging game {}", game.name);
+
+        // Games that have never been run are stored as NULL so they don't show in recents
+        let lastrun = (game.lastrun != 0).then_some(game.lastrun);
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(
+            con,
+            QS_MERGE_GAME,
+            game.id,
+            &*game.name,
+            &*game.overview,
+            game.players,
+            &*game.rating,
+            &*game.released,
+            &*game.crc32,
+            &*game.md5,
+            &*game.sha1,
+            &*game.path,
+            &*game.args,
+            game.platform,
+            &*game.filename
+        )?;
+        execute_update(stmt)?;
+
+        let stmt = create_statement!(
+            con,
+            QS_MERGE_PROFILE_GAME,
+            ProfileInfo::current(),
+            game.platform,
+            &*game.filename,
+            game.favorite as i64,
+            game.playtime,
+            game.launchcount,
+            lastrun
+        )?;
+        execute_update(stmt)
+    }
+
+    /// Updates the last run value and launch count for a game
+    pub fn update_last_run(id: i64, file: &str) -> QueryResult<()> {
+        const QS_UPDATE_GAME_LAST_RUN: &str = "
+        INSERT INTO ProfileGames (profile, platform, filename, lastrun, launchcount)
+        VALUES (@Profile, @Platform, @Game, strftime('%s', 'now', 'localtime'), 1)
+        ON CONFLICT (profile, platform, filename) DO UPDATE
+        SET lastrun = excluded.lastrun, launchcount = COALESCE(launchcount, 0) + 1
+        ";
         crate::logger::info!("Updating last run for game {id}");
 
-        let con = YaffeConnection::new();
-        let stmt = create_statement!(con, QS_UPDATE_GAME_LAST_RUN, id, file);
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_GAME_LAST_RUN, ProfileInfo::current(), id, file)?;
+
+        execute_update(stmt)
+    }
+
+    /// Adds the length of a play session to the total play time of a game
+    pub fn add_play_time(platform: i64, file: &str, seconds: i64) -> QueryResult<()> {
+        const QS_UPDATE_GAME_PLAY_TIME: &str = "
+        INSERT INTO ProfileGames (profile, platform, filename, playtime)
+        VALUES (@Profile, @Platform, @Game, @Seconds)
+        ON CONFLICT (profile, platform, filename) DO UPDATE SET playtime = COALESCE(playtime, 0) + excluded.playtime
+        ";
+        crate::logger::info!("Adding {seconds} seconds of play time to game {file}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_GAME_PLAY_TIME, ProfileInfo::current(), platform, file, seconds)?;
 
         execute_update(stmt)
     }
@@ -0,0 +1,191 @@
+use super::{
+    execute_select, execute_select_once, execute_update, QueryError, QueryResult, Transaction, YaffeConnection,
+};
+use crate::{create_statement, get_column};
+
+enum Step {
+    /// Runs a single sql statement, such as creating a table or an index
+    Sql(&'static str),
+    /// Adds a column to a table
+    /// Databases from before migrations may already have any of these, so existing columns are skipped
+    AddColumn { table: &'static str, column: &'static str, definition: &'static str },
+}
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    steps: &'static [Step],
+}
+
+/// Every change made to the database, in the order they are applied
+/// Migrations must never be changed once released, add a new one instead
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create games and platforms",
+        steps: &[
+            Step::Sql("CREATE TABLE IF NOT EXISTS Games (id INTEGER, name TEXT, overview TEXT, players INTEGER, rating TEXT, released TEXT, filename TEXT, platform INTEGER, lastrun INTEGER)"),
+            Step::Sql("CREATE TABLE IF NOT EXISTS Platforms (id INTEGER, platform TEXT, path TEXT, args TEXT)"),
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "Store rom hashes",
+        steps: &[
+            Step::AddColumn { table: "Games", column: "crc32", definition: "TEXT" },
+            Step::AddColumn { table: "Games", column: "md5", definition: "TEXT" },
+            Step::AddColumn { table: "Games", column: "sha1", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "Allow platforms to prefer a metadata provider",
+        steps: &[Step::AddColumn { table: "Platforms", column: "provider", definition: "TEXT" }],
+    },
+    Migration {
+        version: 4,
+        description: "Game collections",
+        steps: &[
+            Step::Sql("CREATE TABLE IF NOT EXISTS Collections (id INTEGER, name TEXT)"),
+            Step::Sql("CREATE TABLE IF NOT EXISTS CollectionGames (collection INTEGER, platform INTEGER, filename TEXT)"),
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "Per game launch options",
+        steps: &[
+            Step::AddColumn { table: "Games", column: "path", definition: "TEXT" },
+            Step::AddColumn { table: "Games", column: "args", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "Per platform rom extensions",
+        steps: &[Step::AddColumn { table: "Platforms", column: "extensions", definition: "TEXT" }],
+    },
+    Migration {
+        version: 7,
+        description: "Mark games with missing roms",
+        steps: &[Step::AddColumn { table: "Games", column: "missing", definition: "INTEGER" }],
+    },
+    Migration {
+        version: 8,
+        description: "Index games by file",
+        steps: &[
+            Step::Sql("CREATE INDEX IF NOT EXISTS GamesByFile ON Games (platform, filename)"),
+            Step::Sql("CREATE INDEX IF NOT EXISTS CollectionGamesByCollection ON CollectionGames (collection)"),
+            Step::Sql("CREATE INDEX IF NOT EXISTS CollectionGamesByFile ON CollectionGames (platform, filename)"),
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "User profiles",
+        steps: &[
+            Step::Sql("CREATE TABLE IF NOT EXISTS Profiles (id INTEGER, name TEXT)"),
+            Step::Sql("INSERT INTO Profiles (id, name) SELECT 1, 'Default' WHERE NOT EXISTS (SELECT 1 FROM Profiles)"),
+            Step::Sql("CREATE TABLE IF NOT EXISTS ProfileGames (profile INTEGER, platform INTEGER, filename TEXT, lastrun INTEGER, favorite INTEGER, playtime INTEGER, launchcount INTEGER)"),
+            Step::Sql("CREATE UNIQUE INDEX IF NOT EXISTS ProfileGamesByGame ON ProfileGames (profile, platform, filename)"),
+            // Recent games from before profiles belong to the default profile
+            Step::Sql("INSERT OR IGNORE INTO ProfileGames (profile, platform, filename, lastrun) SELECT 1, platform, filename, lastrun FROM Games WHERE lastrun IS NOT NULL"),
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "Persist restricted mode",
+        steps: &[
+            Step::AddColumn { table: "Profiles", column: "passcode", definition: "TEXT" },
+            Step::AddColumn { table: "Profiles", column: "salt", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "Remember the last active profile",
+        steps: &[Step::AddColumn { table: "Profiles", column: "lastactive", definition: "INTEGER" }],
+    },
+    Migration {
+        version: 12,
+        description: "Persist incorrect passcodes",
+        steps: &[
+            Step::AddColumn { table: "Profiles", column: "failedattempts", definition: "INTEGER" },
+            Step::AddColumn { table: "Profiles", column: "lockeduntil", definition: "INTEGER" },
+        ],
+    },
+];
+
+/// Brings the database up to the latest version, backing it up first if it already exists
+pub fn migrate() -> QueryResult<()> {
+    let path = crate::paths::database();
+    let exists = path.exists();
+
+    let version = get_version()?;
+    let pending: Vec<_> = MIGRATIONS.iter().filter(|m| m.version > version).collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    if exists {
+        let backup = path.with_extension(format!("db.v{version}.bak"));
+        crate::logger::info!("Backing up database to {} before migrating", backup.display());
+        std::fs::copy(&path, backup).map_err(QueryError::Backup)?;
+    }
+
+    let con = YaffeConnection::new()?;
+    let stmt = create_statement!(con, "CREATE TABLE IF NOT EXISTS SchemaVersion (version INTEGER)",)?;
+    execute_update(stmt)?;
+    drop(con);
+
+    for m in pending {
+        crate::logger::info!("Migrating database to version {} - {}", m.version, m.description);
+
+        // Each migration is applied completely or not at all
+        let transaction = Transaction::begin()?;
+        apply_migration(m)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+fn apply_migration(migration: &Migration) -> QueryResult<()> {
+    let con = YaffeConnection::new()?;
+    for step in migration.steps {
+        match step {
+            Step::Sql(sql) => execute_update(create_statement!(con, *sql,)?)?,
+            Step::AddColumn { table, column, definition } => {
+                if !get_table_columns(&con, table)?.iter().any(|c| c.eq_ignore_ascii_case(column)) {
+                    let sql = format!("ALTER TABLE {table} ADD COLUMN {column} {definition}");
+                    execute_update(create_statement!(con, sql,)?)?;
+                }
+            }
+        }
+    }
+
+    execute_update(create_statement!(con, "DELETE FROM SchemaVersion",)?)?;
+    execute_update(create_statement!(con, "INSERT INTO SchemaVersion (version) VALUES (@Version)", migration.version)?)
+}
+
+/// Gets the version of the database, which is 0 if it was created before migrations
+fn get_version() -> QueryResult<i64> {
+    const QS_GET_VERSION_TABLE: &str =
+        "SELECT COUNT(1) FROM sqlite_master WHERE type = 'table' AND name = 'SchemaVersion'";
+    const QS_GET_VERSION: &str = "SELECT MAX(version) AS version FROM SchemaVersion";
+
+    let con = YaffeConnection::new()?;
+    let mut stmt = create_statement!(con, QS_GET_VERSION_TABLE,)?;
+    execute_select_once(&mut stmt)?;
+    if get_column!(stmt, i64, 0) == 0 {
+        return Ok(0);
+    }
+
+    let mut stmt = create_statement!(con, QS_GET_VERSION,)?;
+    execute_select_once(&mut stmt)?;
+    Ok(get_column!(stmt, i64, "version"))
+}
+
+fn get_table_columns(con: &YaffeConnection, table: &str) -> QueryResult<Vec<String>> {
+    let stmt = create_statement!(con, format!("PRAGMA table_info({table});"),)?;
+
+    let mut columns = vec![];
+    execute_select(stmt, |r| columns.push(get_column!(r, String, "name")))?;
+    Ok(columns)
+}
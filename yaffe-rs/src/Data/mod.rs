@@ -1,46 +1,70 @@
-use crate::logger::PanicLogEntry;
-use core::ops::Deref;
-
+mod collection;
+mod connection;
 mod game;
+mod migrations;
 mod platform;
-mod schema;
+mod profile;
+pub use collection::CollectionInfo;
+pub use connection::Transaction;
+use connection::YaffeConnection;
 pub use game::GameInfo;
 pub use platform::PlatformInfo;
+pub use profile::{ProfileInfo, DEFAULT_PROFILE};
 
-type QueryResult<T> = Result<T, QueryError>;
+pub type QueryResult<T> = Result<T, QueryError>;
 #[derive(Debug)]
 pub enum QueryError {
     NoResults,
     NoUpdate,
+    /// A transaction was started while this thread already had one open
+    NestedTransaction,
+    Backup(std::io::Error),
+    Sqlite(sqlite::Error),
 }
-
-pub struct YaffeConnection {
-    con: sqlite::Connection,
-}
-impl YaffeConnection {
-    pub fn new() -> YaffeConnection {
-        let connection = sqlite::open("./Yaffe.db").log_and_panic();
-        YaffeConnection { con: connection }
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::NoResults => write!(f, "No results were found"),
+            QueryError::NoUpdate => write!(f, "No rows were updated"),
+            QueryError::NestedTransaction => write!(f, "A transaction is already in progress"),
+            QueryError::Backup(e) => write!(f, "Unable to back up database: {e}"),
+            QueryError::Sqlite(e) => write!(f, "Database error: {e}"),
+        }
     }
 }
-impl Deref for YaffeConnection {
-    type Target = sqlite::Connection;
+impl std::error::Error for QueryError {}
+impl From<sqlite::Error> for QueryError {
+    fn from(v: sqlite::Error) -> Self { QueryError::Sqlite(v) }
+}
 
-    fn deref(&self) -> &sqlite::Connection { &self.con }
+#[macro_export]
+macro_rules! table_struct {
+    ($sv:vis struct $name:ident {
+        $($v:vis $field_name:ident: $field_type:ty,)+
+    }) => {
+        #[allow(dead_code)]
+        #[derive(Default, Clone, Debug)]
+        $sv struct $name { $($v $field_name: $field_type),+ }
+    };
 }
 
 #[macro_export]
 macro_rules! create_statement {
     ($con:ident, $statement:expr, $($x:expr),*) => {{
-        use $crate::logger::PanicLogEntry;
-        #[allow(unused_mut)]
-        let mut statement = $con.prepare($statement).log_message_and_panic("Unable to prepare statement");
-        let mut _i = 1usize;
-    $(
-        statement.bind((_i, $x)).log_and_panic();
-        _i +=1;
-    )*
-    statement
+        match $con.prepare($statement) {
+            #[allow(unused_mut)]
+            Ok(mut statement) => {
+                let mut _i = 1usize;
+                #[allow(unused_mut)]
+                let mut result: Result<(), sqlite::Error> = Ok(());
+            $(
+                result = result.and_then(|_| statement.bind((_i, $x)));
+                _i += 1;
+            )*
+                result.map(|_| statement).map_err($crate::data::QueryError::from)
+            }
+            Err(e) => Err($crate::data::QueryError::from(e)),
+        }
     }};
 }
 
@@ -55,18 +79,19 @@ macro_rules! get_column {
 }
 
 /// Runs the provided function for each row returned from the statement
-fn execute_select<F>(mut stmt: sqlite::Statement, mut f: F)
+fn execute_select<F>(mut stmt: sqlite::Statement, mut f: F) -> QueryResult<()>
 where
     F: FnMut(&sqlite::Statement),
 {
-    while let Ok(sqlite::State::Row) = stmt.next() {
+    while let sqlite::State::Row = stmt.next()? {
         f(&stmt)
     }
+    Ok(())
 }
 
 /// Expect one row to be returned from the statement, otherwise errors
 fn execute_select_once(stmt: &mut sqlite::Statement) -> QueryResult<()> {
-    if let sqlite::State::Row = stmt.next().unwrap() {
+    if let sqlite::State::Row = stmt.next()? {
         return Ok(());
     }
 
@@ -75,22 +100,12 @@ fn execute_select_once(stmt: &mut sqlite::Statement) -> QueryResult<()> {
 
 /// Runs an update statement
 fn execute_update(mut stmt: sqlite::Statement) -> QueryResult<()> {
-    if let sqlite::State::Done = stmt.next().unwrap() {
+    if let sqlite::State::Done = stmt.next()? {
         return Ok(());
     }
 
     Err(QueryError::NoUpdate)
 }
 
-/// Creates the database if it doesn't exist
-pub fn init_database() -> QueryResult<()> {
-    if !std::path::Path::new("./Yaffe.db").exists() {
-        schema::create_schema("Games", GameInfo::default())?;
-        schema::create_schema("Platforms", PlatformInfo::default())?;
-    }
-
-    schema::update_schema("Games", GameInfo::default())?;
-    schema::update_schema("Platforms", PlatformInfo::default())?;
-
-    Ok(())
-}
+/// Creates the database if it doesn't exist and applies any pending migrations
+pub fn init_database() -> QueryResult<()> { migrations::migrate() }
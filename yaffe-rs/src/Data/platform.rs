@@ -7,44 +7,108 @@ crate::table_struct!(
         pub platform: String,
         pub path: String,
         pub args: String,
+        pub provider: String,
+        pub extensions: String,
     }
 );
+
+/// Rom extensions used by common emulators, matched in order against the platform name
+const DEFAULT_EXTENSIONS: &[(&str, &str)] = &[
+    ("Super Nintendo", "sfc,smc,fig,swc,bs,zip,7z"),
+    ("Nintendo Entertainment System", "nes,fds,unf,zip,7z"),
+    ("Nintendo 64", "n64,z64,v64,zip,7z"),
+    ("Nintendo Game Boy Advance", "gba,zip,7z"),
+    ("Nintendo Game Boy Color", "gbc,gb,zip,7z"),
+    ("Nintendo Game Boy", "gb,zip,7z"),
+    ("Nintendo DS", "nds,zip,7z"),
+    ("Nintendo GameCube", "iso,gcm,gcz,rvz,ciso"),
+    ("Nintendo Wii", "iso,wbfs,rvz,wad"),
+    ("Sega Genesis", "md,gen,smd,bin,zip,7z"),
+    ("Sega Mega Drive", "md,gen,smd,bin,zip,7z"),
+    ("Sega Master System", "sms,zip,7z"),
+    ("Sega Game Gear", "gg,zip,7z"),
+    ("Sega Saturn", "cue,chd,m3u,iso"),
+    ("Sega Dreamcast", "gdi,cdi,chd,m3u"),
+    ("Sony Playstation Portable", "iso,cso,pbp"),
+    ("Sony Playstation 2", "iso,chd,cso,m3u"),
+    ("Sony Playstation", "cue,chd,pbp,m3u,iso"),
+    ("Atari 2600", "a26,bin,zip,7z"),
+    ("Arcade", "zip,7z"),
+];
+
 impl PlatformInfo {
     pub fn new(id: i64, platform: String, path: String, args: String) -> PlatformInfo {
-        PlatformInfo { id, platform, path, args }
+        let extensions = PlatformInfo::default_extensions(&platform).to_string();
+        PlatformInfo { id, platform, path, args, provider: String::new(), extensions }
+    }
+
+    /// Gets the rom extensions commonly used by a platform, empty if it isn't known
+    pub fn default_extensions(platform: &str) -> &'static str {
+        let platform = platform.to_lowercase();
+        DEFAULT_EXTENSIONS
+            .iter()
+            .find(|(name, _)| platform.contains(&name.to_lowercase()))
+            .map(|(_, extensions)| *extensions)
+            .unwrap_or_default()
     }
 
     /// Adds a new platform
     pub fn insert(platform: &PlatformInfo) -> QueryResult<()> {
         const QS_ADD_PLATFORM: &str = "
         INSERT INTO Platforms
-        (id, platform, path, args)
+        (id, platform, path, args, provider, extensions)
         VALUES
-        (@PlatformId, @Platform, @Path, @Args)
+        (@PlatformId, @Platform, @Path, @Args, @Provider, @Extensions)
         ";
         crate::logger::info!("Inserting new platform into database {}", platform.platform);
 
-        let con = YaffeConnection::new();
-        let stmt =
-            create_statement!(con, QS_ADD_PLATFORM, platform.id, &*platform.platform, &*platform.path, &*platform.args);
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(
+            con,
+            QS_ADD_PLATFORM,
+            platform.id,
+            &*platform.platform,
+            &*platform.path,
+            &*platform.args,
+            &*platform.provider,
+            &*platform.extensions
+        )?;
 
         execute_update(stmt)
     }
 
     /// Updates attributes of an existing platform
-    pub fn update(platform: i64, exe: &str, args: &str) -> QueryResult<()> {
-        const QS_UPDATE_PLATFORM: &str = "UPDATE Platforms SET path = @Path, args = @Args WHERE id = @ID";
-        let con = YaffeConnection::new();
+    pub fn update(platform: i64, exe: &str, args: &str, provider: &str, extensions: &str) -> QueryResult<()> {
+        const QS_UPDATE_PLATFORM: &str = "UPDATE Platforms SET path = @Path, args = @Args, provider = @Provider, extensions = @Extensions WHERE id = @ID";
+        let con = YaffeConnection::new()?;
 
-        let stmt = create_statement!(con, QS_UPDATE_PLATFORM, exe, args, platform);
+        let stmt = create_statement!(con, QS_UPDATE_PLATFORM, exe, args, provider, extensions, platform)?;
         execute_update(stmt)
     }
 
+    /// Gets the metadata provider a platform prefers, empty if it uses the default order
+    pub fn get_provider(platform: i64) -> QueryResult<String> {
+        const QS_GET_PLATFORM_PROVIDER: &str = "SELECT provider FROM Platforms WHERE id = @ID";
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_PLATFORM_PROVIDER, platform)?;
+        execute_select_once(&mut stmt)?;
+        Ok(get_column!(stmt, String, "provider"))
+    }
+
+    /// Gets the comma separated rom extensions allowed for a platform, empty if any rom is allowed
+    pub fn get_extensions(platform: i64) -> QueryResult<String> {
+        const QS_GET_PLATFORM_EXTENSIONS: &str = "SELECT extensions FROM Platforms WHERE id = @ID";
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_PLATFORM_EXTENSIONS, platform)?;
+        execute_select_once(&mut stmt)?;
+        Ok(get_column!(stmt, String, "extensions"))
+    }
+
     /// Gets the name of a platform
     pub fn get_name(platform: i64) -> QueryResult<String> {
         const QS_GET_PLATFORM_NAME: &str = "SELECT platform FROM Platforms WHERE id = @ID";
-        let con = YaffeConnection::new();
-        let mut stmt = create_statement!(con, QS_GET_PLATFORM_NAME, platform);
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_PLATFORM_NAME, platform)?;
         execute_select_once(&mut stmt)?;
         Ok(get_column!(stmt, String, "platform"))
     }
@@ -54,20 +118,21 @@ impl PlatformInfo {
         const QS_GET_PLATFORM: &str = "SELECT path, args FROM Platforms WHERE id = @ID";
         crate::logger::info!("Getting information for platform {platform}");
 
-        let con = YaffeConnection::new();
-        let mut stmt = create_statement!(con, QS_GET_PLATFORM, platform);
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_PLATFORM, platform)?;
         execute_select_once(&mut stmt)?;
 
         Ok((get_column!(stmt, String, "path"), get_column!(stmt, String, "args")))
     }
 
     /// Gets all saved platforms
-    pub fn get_all() -> Vec<PlatformInfo> {
-        const QS_GET_ALL_PLATFORMS: &str = "SELECT id, platform, path, args FROM Platforms ORDER BY platform";
+    pub fn get_all() -> QueryResult<Vec<PlatformInfo>> {
+        const QS_GET_ALL_PLATFORMS: &str =
+            "SELECT id, platform, path, args, provider, extensions FROM Platforms ORDER BY platform";
         crate::logger::info!("Loading all platforms from database");
 
-        let con = YaffeConnection::new();
-        let stmt = create_statement!(con, QS_GET_ALL_PLATFORMS,);
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_ALL_PLATFORMS,)?;
 
         let mut result = vec![];
         execute_select(stmt, |r| {
@@ -75,9 +140,11 @@ impl PlatformInfo {
             let platform = get_column!(r, String, "platform");
             let path = get_column!(r, String, "path");
             let args = get_column!(r, String, "args");
-            result.push(PlatformInfo { id, platform, path, args });
-        });
+            let provider = get_column!(r, String, "provider");
+            let extensions = get_column!(r, String, "extensions");
+            result.push(PlatformInfo { id, platform, path, args, provider, extensions });
+        })?;
 
-        result
+        Ok(result)
     }
 }
@@ -0,0 +1,114 @@
+use super::{execute_select, execute_select_once, execute_update, QueryResult, YaffeConnection};
+use crate::{create_statement, get_column};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Profile that is created with the database and owns history from before profiles existed
+pub const DEFAULT_PROFILE: i64 = 1;
+
+static CURRENT_PROFILE: AtomicI64 = AtomicI64::new(DEFAULT_PROFILE);
+
+crate::table_struct!(
+    pub struct ProfileInfo {
+        pub id: i64,
+        pub name: String,
+        /// Salted hash of the restricted mode passcode, empty when restricted mode is off
+        pub passcode: String,
+        pub salt: String,
+        /// Incorrect passcodes entered since the last correct one
+        pub failedattempts: i64,
+        /// Unix time until which no passcode can be entered
+        pub lockeduntil: i64,
+    }
+);
+
+impl ProfileInfo {
+    /// Profile that recents, favorites, and play time are read from and saved to
+    pub fn current() -> i64 { CURRENT_PROFILE.load(Ordering::Acquire) }
+
+    pub fn set_current(id: i64) {
+        crate::logger::info!("Switching to profile {id}");
+        CURRENT_PROFILE.store(id, Ordering::Release);
+    }
+
+    /// Profile that was being used when Yaffe was last closed
+    pub fn get_last_active() -> QueryResult<i64> {
+        const QS_GET_LAST_ACTIVE: &str = "SELECT COALESCE(MAX(id), @Default) FROM Profiles WHERE lastactive = 1";
+        crate::logger::info!("Getting last active profile");
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_LAST_ACTIVE, DEFAULT_PROFILE)?;
+        execute_select_once(&mut stmt)?;
+        Ok(get_column!(stmt, i64, 0))
+    }
+
+    /// Saves which profile is being used so it is selected again after restarting
+    pub fn set_last_active(id: i64) -> QueryResult<()> {
+        const QS_UPDATE_LAST_ACTIVE: &str = "UPDATE Profiles SET lastactive = (id = @ProfileId)";
+        crate::logger::info!("Updating last active profile to {id}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_LAST_ACTIVE, id)?;
+        execute_update(stmt)
+    }
+
+    /// Gets all profiles in the order they were created
+    pub fn get_all() -> QueryResult<Vec<ProfileInfo>> {
+        const QS_GET_ALL_PROFILES: &str =
+            "SELECT id, name, passcode, salt, failedattempts, lockeduntil FROM Profiles ORDER BY id";
+        crate::logger::info!("Getting all profiles");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_GET_ALL_PROFILES,)?;
+
+        let mut result = vec![];
+        execute_select(stmt, |r| {
+            let id = get_column!(r, i64, "id");
+            let name = get_column!(r, String, "name");
+            let passcode = get_column!(r, String, "passcode");
+            let salt = get_column!(r, String, "salt");
+            let failedattempts = get_column!(r, i64, "failedattempts");
+            let lockeduntil = get_column!(r, i64, "lockeduntil");
+            result.push(ProfileInfo { id, name, passcode, salt, failedattempts, lockeduntil });
+        })?;
+
+        Ok(result)
+    }
+
+    /// Adds a new profile, returning its id
+    pub fn insert(name: &str) -> QueryResult<i64> {
+        const QS_GET_NEXT_ID: &str = "SELECT COALESCE(MAX(id), 0) + 1 FROM Profiles";
+        const QS_ADD_PROFILE: &str = "INSERT INTO Profiles (id, name) VALUES (@ProfileId, @Name)";
+        crate::logger::info!("Inserting new profile into database {name}");
+
+        let con = YaffeConnection::new()?;
+        let mut stmt = create_statement!(con, QS_GET_NEXT_ID,)?;
+        execute_select_once(&mut stmt)?;
+        let id = get_column!(stmt, i64, 0);
+
+        let stmt = create_statement!(con, QS_ADD_PROFILE, id, name)?;
+        execute_update(stmt)?;
+        Ok(id)
+    }
+
+    /// Saves the hashed restricted mode passcode of a profile, clearing it turns restricted mode off
+    pub fn set_passcode(id: i64, passcode: &str, salt: &str) -> QueryResult<()> {
+        const QS_UPDATE_PROFILE_PASSCODE: &str =
+            "UPDATE Profiles SET passcode = @Passcode, salt = @Salt WHERE id = @ProfileId";
+        crate::logger::info!("Updating restricted mode of profile {id}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_PROFILE_PASSCODE, passcode, salt, id)?;
+        execute_update(stmt)
+    }
+
+    /// Saves incorrect passcodes so restarting doesn't allow more guesses
+    pub fn set_attempts(id: i64, failed: i64, locked_until: i64) -> QueryResult<()> {
+        const QS_UPDATE_PROFILE_ATTEMPTS: &str =
+            "UPDATE Profiles SET failedattempts = @Failed, lockeduntil = @LockedUntil WHERE id = @ProfileId";
+        crate::logger::info!("Updating incorrect passcodes of profile {id}");
+
+        let con = YaffeConnection::new()?;
+        let stmt = create_statement!(con, QS_UPDATE_PROFILE_ATTEMPTS, failed, locked_until, id)?;
+        execute_update(stmt)
+    }
+}
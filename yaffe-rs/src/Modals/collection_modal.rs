@@ -0,0 +1,154 @@
+use crate::controls::{CheckBox, List, TextBox};
+use crate::data::{CollectionInfo, QueryResult};
+use crate::logger::UserMessage;
+use crate::modals::{DisplayModal, ModalContentElement, ModalInputHandler, ModalSize, ModalValidationResult};
+use crate::ui::{ContainerSize, UiContainer, ValueElement};
+use crate::{DeferredAction, YaffeState};
+
+const NEW_COLLECTION: &str = "New Collection";
+
+/// Lists all collections to either edit one or add a game to it
+pub struct CollectionsModal {
+    collections: Vec<CollectionInfo>,
+    game: Option<(i64, String)>,
+}
+
+impl CollectionsModal {
+    pub fn manage() -> QueryResult<ModalContentElement<YaffeState>> { CollectionsModal::_init(None) }
+
+    pub fn add_game(platform: i64, file: &str) -> QueryResult<ModalContentElement<YaffeState>> {
+        CollectionsModal::_init(Some((platform, file.to_string())))
+    }
+
+    fn _init(game: Option<(i64, String)>) -> QueryResult<ModalContentElement<YaffeState>> {
+        let collections = CollectionInfo::get_all()?;
+
+        let mut items = vec![NEW_COLLECTION.to_string()];
+        items.extend(collections.iter().map(|c| c.name.clone()));
+
+        let mut modal = ModalContentElement::new(CollectionsModal { collections, game }, false);
+        modal.add_child(List::from(items), ContainerSize::Shrink);
+        Ok(modal)
+    }
+}
+
+impl ModalInputHandler<YaffeState> for CollectionsModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let list = crate::convert_to!(content.get_child(0), List<String>);
+        // First item is always to create a new collection
+        let collection = match list.get_selected_index() {
+            0 => None,
+            i => Some(&self.collections[i - 1]),
+        };
+
+        match (collection, &self.game) {
+            (Some(collection), Some((platform, file))) => {
+                if CollectionInfo::add_game(collection.id, *platform, file)
+                    .display_failure("Unable to add game to collection", handler)
+                    .is_some()
+                {
+                    handler.display_toast(&format!("Added to {}", collection.name), 2.);
+                    state.refresh_list = true;
+                }
+            }
+            (Some(collection), None) => {
+                let content = CollectionDetailModal::from_existing(collection);
+                handler.display_modal(DisplayModal::new("Edit Collection", Some("Save"), content, ModalSize::Third));
+            }
+            (None, game) => {
+                let content = CollectionDetailModal::new_collection(game.clone());
+                handler.display_modal(DisplayModal::new(NEW_COLLECTION, Some("Create"), content, ModalSize::Third));
+            }
+        }
+    }
+}
+
+/// Creates, renames, or deletes a single collection
+pub struct CollectionDetailModal {
+    collection: Option<CollectionInfo>,
+    game: Option<(i64, String)>,
+}
+
+impl CollectionDetailModal {
+    pub fn new_collection(game: Option<(i64, String)>) -> ModalContentElement<YaffeState> {
+        let mut modal = ModalContentElement::new(CollectionDetailModal { collection: None, game }, true);
+        modal.add_child(TextBox::from("Name", ""), ContainerSize::Shrink);
+        modal.focus_first();
+        modal
+    }
+
+    pub fn from_existing(collection: &CollectionInfo) -> ModalContentElement<YaffeState> {
+        let name = TextBox::from("Name", &collection.name);
+        let content = CollectionDetailModal { collection: Some(collection.clone()), game: None };
+
+        let mut modal = ModalContentElement::new(content, true);
+        modal
+            .add_child(name, ContainerSize::Shrink)
+            .add_child(CheckBox::from("Delete".to_string(), false), ContainerSize::Shrink);
+        modal.focus_first();
+        modal
+    }
+}
+
+impl ModalInputHandler<YaffeState> for CollectionDetailModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn validate(&self, content: &UiContainer<YaffeState>) -> ModalValidationResult {
+        let name = crate::convert_to!(content.get_child(0), TextBox).value();
+        if name.is_empty() {
+            ModalValidationResult::Cancel(String::from("Name is required"))
+        } else {
+            ModalValidationResult::Ok
+        }
+    }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let name = crate::convert_to!(content.get_child(0), TextBox).value();
+        match &self.collection {
+            Some(collection) => {
+                let delete = crate::convert_to!(content.get_child(1), CheckBox).value();
+                if delete {
+                    CollectionInfo::delete(collection.id).display_failure("Unable to delete collection", handler);
+                    // The selected group may no longer exist
+                    state.selected = crate::state::SelectedItem::new();
+                } else {
+                    CollectionInfo::rename(collection.id, &name)
+                        .display_failure("Unable to rename collection", handler);
+                }
+            }
+            None => {
+                if let Some(id) = CollectionInfo::insert(&name).display_failure("Unable to create collection", handler)
+                {
+                    if let Some((platform, file)) = &self.game {
+                        CollectionInfo::add_game(id, *platform, file)
+                            .display_failure("Unable to add game to collection", handler);
+                    }
+                }
+            }
+        }
+
+        state.refresh_list = true;
+    }
+}
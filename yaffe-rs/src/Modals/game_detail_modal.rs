@@ -0,0 +1,114 @@
+use crate::controls::TextBox;
+use crate::data::{GameInfo, QueryResult};
+use crate::logger::{LogEntry, UserMessage};
+use crate::modals::{ModalContentElement, ModalInputHandler, ModalValidationResult};
+use crate::ui::{ContainerSize, LayoutElement, UiContainer, ValueElement, WidgetId};
+use crate::{DeferredAction, YaffeState};
+use std::collections::HashMap;
+
+pub struct GameDetailModal {
+    control_map: HashMap<String, WidgetId>,
+    info: GameInfo,
+}
+
+impl GameDetailModal {
+    pub fn from_existing(platform: i64, file: &str) -> QueryResult<ModalContentElement<YaffeState>> {
+        let info = GameInfo::get(platform, file)?;
+
+        let name = TextBox::from("Name", &info.name);
+        let overview = TextBox::from("Overview", &info.overview);
+        let players = TextBox::from("Players", &info.players.to_string());
+        let rating = TextBox::from("Rating", &info.rating);
+        let released = TextBox::from("Released", &info.released);
+        // Leaving these empty uses the values from the platform
+        let executable = TextBox::from("Executable", &info.path);
+        let args = TextBox::from("Args", &info.args);
+
+        let mut control_map = HashMap::new();
+        control_map.insert("Name".to_string(), name.get_id());
+        control_map.insert("Overview".to_string(), overview.get_id());
+        control_map.insert("Players".to_string(), players.get_id());
+        control_map.insert("Rating".to_string(), rating.get_id());
+        control_map.insert("Released".to_string(), released.get_id());
+        control_map.insert("Executable".to_string(), executable.get_id());
+        control_map.insert("Args".to_string(), args.get_id());
+
+        let detail = GameDetailModal { control_map, info };
+        let mut modal = ModalContentElement::new(detail, true);
+        modal
+            .add_child(name, ContainerSize::Shrink)
+            .add_child(overview, ContainerSize::Shrink)
+            .add_child(players, ContainerSize::Shrink)
+            .add_child(rating, ContainerSize::Shrink)
+            .add_child(released, ContainerSize::Shrink)
+            .add_child(executable, ContainerSize::Shrink)
+            .add_child(args, ContainerSize::Shrink);
+        modal.focus_first();
+        Ok(modal)
+    }
+
+    fn get_value(&self, content: &UiContainer<YaffeState>, name: &str) -> String {
+        let control = self.control_map[name];
+        crate::convert_to!(content.find_widget(control).unwrap(), TextBox).value()
+    }
+}
+
+impl ModalInputHandler<YaffeState> for GameDetailModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn validate(&self, content: &UiContainer<YaffeState>) -> ModalValidationResult {
+        let mut validation = vec![];
+        if self.get_value(content, "Name").is_empty() {
+            validation.push("Name is required");
+        }
+        if self.get_value(content, "Players").parse::<i64>().is_err() {
+            validation.push("Players must be a number");
+        }
+        if crate::launch_args::validate_launch_args(&self.get_value(content, "Args")).is_err() {
+            validation.push("Args has an unterminated quote or unknown placeholder");
+        }
+        if validation.is_empty() {
+            ModalValidationResult::Ok
+        } else {
+            ModalValidationResult::Cancel(validation.join("\n"))
+        }
+    }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let mut info = self.info.clone();
+        info.name = self.get_value(content, "Name");
+        info.overview = self.get_value(content, "Overview");
+        info.players = self.get_value(content, "Players").parse::<i64>().unwrap_or(info.players);
+        info.rating = self.get_value(content, "Rating");
+        info.released = self.get_value(content, "Released");
+        info.path = self.get_value(content, "Executable");
+        info.args = self.get_value(content, "Args");
+
+        if GameInfo::update(&info).display_failure("Unable to update game", handler).is_none() {
+            return;
+        }
+
+        // Boxart is stored by game name so it needs to follow a rename
+        if info.name != self.info.name {
+            if let Ok(platform) = crate::data::PlatformInfo::get_name(info.platform()) {
+                let old_path = crate::assets::get_asset_path(&platform, &self.info.name);
+                if old_path.exists() {
+                    let new_path = crate::assets::get_asset_path(&platform, &info.name);
+                    std::fs::rename(old_path, new_path).log("Unable to rename boxart");
+                }
+            }
+        }
+
+        state.refresh_list = true;
+    }
+}
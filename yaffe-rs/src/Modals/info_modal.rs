@@ -1,19 +1,25 @@
-use crate::modals::{ModalContentElement, ModalInputHandler};
+use crate::logger::UserMessage;
+use crate::modals::{DisplayModal, GameDetailModal, ModalContentElement, ModalInputHandler, ModalSize};
 use crate::ui::{ContainerSize, UiContainer};
 use crate::widgets::InfoPane;
 use crate::{DeferredAction, Tile, YaffeState};
 
-pub struct InfoModal;
+pub struct InfoModal {
+    platform: i64,
+    file: String,
+    editable: bool,
+}
 
 impl InfoModal {
-    pub fn from(items: &Tile) -> ModalContentElement<YaffeState> {
+    pub fn from(items: &Tile, editable: bool) -> ModalContentElement<YaffeState> {
         let mut attributes = vec![];
         for (name, value) in &items.metadata {
             attributes.push((name.clone(), value.clone()))
         }
         let pane = InfoPane::from(items.boxart.clone(), items.description.clone(), attributes);
 
-        let mut modal = ModalContentElement::new(InfoModal, false);
+        let mut modal =
+            ModalContentElement::new(InfoModal { platform: items.group_id, file: items.file.clone(), editable }, false);
         modal.with_child(UiContainer::row(), ContainerSize::Percent(0.60)).add_child(pane, ContainerSize::Fill);
         modal
     }
@@ -22,5 +28,18 @@ impl InfoModal {
 impl ModalInputHandler<YaffeState> for InfoModal {
     fn as_any(&self) -> &dyn std::any::Any { self }
 
-    fn on_close(&self, _: &mut YaffeState, _: bool, _: &UiContainer<YaffeState>, _: &mut DeferredAction<YaffeState>) {}
+    fn on_close(
+        &self,
+        _: &mut YaffeState,
+        result: bool,
+        _: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if result && self.editable {
+            let content = GameDetailModal::from_existing(self.platform, &self.file);
+            if let Some(content) = content.display_failure("Unable to load game", handler) {
+                handler.display_modal(DisplayModal::new("Edit Game", Some("Save"), content, ModalSize::Half));
+            }
+        }
+    }
 }
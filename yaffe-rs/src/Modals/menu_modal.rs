@@ -1,9 +1,11 @@
 use crate::controls::List;
 use crate::logger::UserMessage;
 use crate::modals::{
-    DisplayModal, ModalContentElement, ModalInputHandler, ModalSize, PlatformDetailModal, SetRestrictedModal,
-    SettingsModal,
+    CollectionsModal, DisplayModal, MissingRomsModal, ModalContentElement, ModalInputHandler, ModalSize,
+    PlatformDetailModal, ProfilesModal, SetRestrictedModal, SettingsModal,
 };
+use crate::restrictions::RestrictedAction;
+use crate::state::GroupType;
 use crate::ui::{ContainerSize, UiContainer};
 use crate::{DeferredAction, YaffeState};
 
@@ -31,6 +33,10 @@ impl ModalInputHandler<YaffeState> for MenuModal {
         if result {
             let elements = crate::convert_to!(content.get_child(0), crate::controls::List<String>);
             let selected = elements.get_selected().as_str();
+            if !crate::modals::verify_restricted_action(state, RestrictedAction::Menu(selected)) {
+                handler.display_toast(&format!("{selected} is locked by restricted mode"), 2.);
+                return;
+            }
 
             match selected {
                 "Add Emulator" => {
@@ -47,6 +53,10 @@ impl ModalInputHandler<YaffeState> for MenuModal {
                     handler.display_modal(DisplayModal::new("Settings", Some("Confirm"), content, ModalSize::Third));
                 }
                 "Disable Restricted Mode" | "Enable Restricted Mode" => {
+                    if crate::modals::is_locked_out(state, handler) {
+                        return;
+                    }
+
                     let content = SetRestrictedModal::new();
                     handler.display_modal(DisplayModal::new(
                         "Restricted Mode",
@@ -55,7 +65,89 @@ impl ModalInputHandler<YaffeState> for MenuModal {
                         ModalSize::Third,
                     ));
                 }
+                "Switch Profile" => {
+                    let content = ProfilesModal::load();
+                    if let Some(content) = content.display_failure("Unable to get profiles", handler) {
+                        handler.display_modal(DisplayModal::new(
+                            "Switch Profile",
+                            Some("Select"),
+                            content,
+                            ModalSize::Third,
+                        ));
+                    }
+                }
                 "Scan For New Roms" => crate::platform::scan_new_files(state, handler),
+                "Clean Up Missing Roms" => {
+                    let games = crate::platform::find_missing_games(state);
+                    match games.display_failure("Unable to check for missing roms", handler) {
+                        Some(games) if games.is_empty() => {
+                            let platforms = crate::data::PlatformInfo::get_all();
+                            if let Some(platforms) = platforms.display_failure("Unable to get platforms", handler) {
+                                let boxart: usize =
+                                    platforms.iter().map(|p| crate::platform::remove_orphaned_boxart(p.id)).sum();
+                                handler.display_toast(
+                                    &format!("No missing roms found, removed {boxart} unused boxart"),
+                                    2.,
+                                );
+                            }
+                        }
+                        Some(games) => {
+                            let content = MissingRomsModal::from(state, games);
+                            handler.display_modal(DisplayModal::new(
+                                "Missing Roms",
+                                Some("Select"),
+                                content,
+                                ModalSize::Half,
+                            ));
+                        }
+                        None => {}
+                    }
+                }
+                "Search Game Again" => {
+                    if let Some(tile) = state.get_selected_tile() {
+                        crate::platform::rescrape_game(state, tile.group_id, &tile.file);
+                    }
+                }
+                "Add To Collection" => {
+                    if let Some(tile) = state.get_selected_tile() {
+                        let content = CollectionsModal::add_game(tile.group_id, &tile.file);
+                        if let Some(content) = content.display_failure("Unable to get collections", handler) {
+                            handler.display_modal(DisplayModal::new(
+                                "Add To Collection",
+                                Some("Add"),
+                                content,
+                                ModalSize::Third,
+                            ));
+                        }
+                    }
+                }
+                "Remove From Collection" => {
+                    if let (GroupType::Collection(id), Some(tile)) =
+                        (state.get_selected_group().kind, state.get_selected_tile())
+                    {
+                        if crate::data::CollectionInfo::remove_game(id, tile.group_id, &tile.file)
+                            .display_failure("Unable to remove game from collection", handler)
+                            .is_some()
+                        {
+                            state.refresh_list = true;
+                        }
+                    }
+                }
+                "Collections" => {
+                    let content = CollectionsModal::manage();
+                    if let Some(content) = content.display_failure("Unable to get collections", handler) {
+                        handler.display_modal(DisplayModal::new(
+                            "Collections",
+                            Some("Select"),
+                            content,
+                            ModalSize::Third,
+                        ));
+                    }
+                }
+                "Search Platform Games Again" => {
+                    let platform = state.get_selected_group().id;
+                    crate::platform::rescrape_platform(state, platform, handler);
+                }
                 "Exit Yaffe" => state.exit(),
                 "Shut Down" => {
                     if crate::os::shutdown().display_failure("Failed to shut down", handler).is_some() {
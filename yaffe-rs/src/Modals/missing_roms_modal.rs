@@ -0,0 +1,156 @@
+use crate::controls::{CheckBox, List, TextBox};
+use crate::data::GameInfo;
+use crate::logger::UserMessage;
+use crate::modals::{DisplayModal, ModalContentElement, ModalInputHandler, ModalSize, ModalValidationResult};
+use crate::ui::{ContainerSize, UiContainer, ValueElement};
+use crate::{DeferredAction, YaffeState};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const REMOVE_ALL: &str = "Remove All Missing Games";
+
+/// Lists every game whose rom can no longer be found
+pub struct MissingRomsModal {
+    games: Vec<GameInfo>,
+}
+
+impl MissingRomsModal {
+    pub fn from(state: &YaffeState, games: Vec<GameInfo>) -> ModalContentElement<YaffeState> {
+        let mut items = vec![REMOVE_ALL.to_string()];
+        for g in &games {
+            let platform = state.find_platform(g.platform()).map(|p| p.name.as_str()).unwrap_or_default();
+            items.push(format!("{} ({platform}/{})", g.name, g.filename));
+        }
+
+        let mut modal = ModalContentElement::new(MissingRomsModal { games }, false);
+        modal.add_child(List::from(items), ContainerSize::Shrink);
+        modal
+    }
+}
+
+impl ModalInputHandler<YaffeState> for MissingRomsModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let list = crate::convert_to!(content.get_child(0), List<String>);
+        match list.get_selected_index() {
+            0 => {
+                let mut platforms = HashSet::new();
+                let mut count = 0;
+                for g in &self.games {
+                    if GameInfo::delete(g.platform(), &g.filename)
+                        .display_failure("Unable to remove game", handler)
+                        .is_some()
+                    {
+                        platforms.insert(g.platform());
+                        count += 1;
+                    }
+                }
+
+                let boxart: usize = platforms.into_iter().map(crate::platform::remove_orphaned_boxart).sum();
+                handler.display_toast(&format!("Removed {count} missing games and {boxart} unused boxart"), 2.);
+                state.refresh_list = true;
+            }
+            i => {
+                let game = &self.games[i - 1];
+                let content = MissingRomDetailModal::from(state, game);
+                handler.display_modal(DisplayModal::new("Missing Rom", Some("Save"), content, ModalSize::Third));
+            }
+        }
+    }
+}
+
+/// Removes a single missing game or points it to where its rom is now
+pub struct MissingRomDetailModal {
+    info: GameInfo,
+    rom_path: PathBuf,
+}
+
+impl MissingRomDetailModal {
+    pub fn from(state: &YaffeState, info: &GameInfo) -> ModalContentElement<YaffeState> {
+        let rom_path = state.find_platform(info.platform()).and_then(|g| g.get_rom_path().ok()).unwrap_or_default();
+
+        let file = TextBox::from("File", &info.filename);
+        let remove = CheckBox::from("Remove".to_string(), false);
+
+        let mut modal = ModalContentElement::new(MissingRomDetailModal { info: info.clone(), rom_path }, true);
+        modal.add_child(file, ContainerSize::Shrink).add_child(remove, ContainerSize::Shrink);
+        modal.focus_first();
+        modal
+    }
+
+    /// Gets the new rom path relative to the platform folder, which can also be given as a full path
+    fn get_file(&self, content: &UiContainer<YaffeState>) -> String {
+        let file = crate::convert_to!(content.get_child(0), TextBox).value();
+        let path = Path::new(&file);
+        if path.is_absolute() && path.starts_with(&self.rom_path) {
+            crate::rom_scan::get_relative_path(&self.rom_path, path)
+        } else {
+            file.replace('\\', "/")
+        }
+    }
+}
+
+impl ModalInputHandler<YaffeState> for MissingRomDetailModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn validate(&self, content: &UiContainer<YaffeState>) -> ModalValidationResult {
+        let remove = crate::convert_to!(content.get_child(1), CheckBox).value();
+        if remove {
+            return ModalValidationResult::Ok;
+        }
+
+        let file = self.get_file(content);
+        if file.is_empty() || !self.rom_path.join(&file).is_file() {
+            return ModalValidationResult::Cancel(format!("{file} does not exist in the platform folder"));
+        }
+        if file != self.info.filename && GameInfo::exists(self.info.platform(), &file).unwrap_or(false) {
+            return ModalValidationResult::Cancel(format!("{file} already belongs to another game"));
+        }
+        ModalValidationResult::Ok
+    }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let platform = self.info.platform();
+        let remove = crate::convert_to!(content.get_child(1), CheckBox).value();
+        if remove {
+            if GameInfo::delete(platform, &self.info.filename)
+                .display_failure("Unable to remove game", handler)
+                .is_some()
+            {
+                crate::platform::remove_orphaned_boxart(platform);
+                handler.display_toast(&format!("Removed {}", self.info.name), 2.);
+            }
+        } else {
+            let file = self.get_file(content);
+            if GameInfo::relink(platform, &self.info.filename, &file)
+                .display_failure("Unable to relink game", handler)
+                .is_some()
+            {
+                handler.display_toast(&format!("Relinked {} to {file}", self.info.name), 2.);
+            }
+        }
+
+        state.refresh_list = true;
+    }
+}
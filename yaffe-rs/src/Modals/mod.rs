@@ -6,23 +6,31 @@ use crate::ui::{
 use crate::{Actions, LogicalPosition, LogicalSize, Rect};
 use std::ops::{Deref, DerefMut};
 
+mod collection_modal;
+mod game_detail_modal;
 mod info_modal;
 mod menu_modal;
 mod message_modal;
+mod missing_roms_modal;
 mod modal_content;
 mod modal_deferred_actions;
 mod platform_detail_modal;
+mod profile_modal;
 mod restricted_modal;
 mod scraper_modal;
 mod settings_modal;
 
+pub use collection_modal::{CollectionDetailModal, CollectionsModal};
+pub use game_detail_modal::GameDetailModal;
 pub use info_modal::InfoModal;
 pub use menu_modal::MenuModal;
 pub use message_modal::MessageModal;
+pub use missing_roms_modal::MissingRomsModal;
 pub use modal_content::ModalContentElement;
 pub use modal_deferred_actions::{DisplayModal, ModalClose};
 pub use platform_detail_modal::PlatformDetailModal;
-pub use restricted_modal::{verify_restricted_action, RestrictedMode, SetRestrictedModal};
+pub use profile_modal::ProfilesModal;
+pub use restricted_modal::{is_locked_out, verify_restricted_action, RestrictedMode, SetRestrictedModal};
 pub use scraper_modal::ScraperModal;
 pub use settings_modal::SettingsModal;
 
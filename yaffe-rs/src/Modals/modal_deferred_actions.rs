@@ -75,4 +75,6 @@ impl<T> DeferredActionTrait<T> for Toast {
         ui.display_toast(*self);
         None
     }
+
+    fn message(&self) -> Option<&str> { Some(&self.message) }
 }
@@ -1,5 +1,6 @@
 use crate::controls::TextBox;
-use crate::logger::{PanicLogEntry, UserMessage};
+use crate::data::QueryResult;
+use crate::logger::UserMessage;
 use crate::modals::{ModalContentElement, ModalInputHandler, ModalValidationResult};
 use crate::ui::{ContainerSize, LayoutElement, UiContainer, ValueElement, WidgetId};
 use crate::{DeferredAction, YaffeState};
@@ -12,25 +13,38 @@ pub struct PlatformDetailModal {
 }
 
 impl PlatformDetailModal {
-    pub fn emulator() -> ModalContentElement<YaffeState> { PlatformDetailModal::_init(0, "", "", "", false) }
+    pub fn emulator() -> ModalContentElement<YaffeState> { PlatformDetailModal::_init(0, "", "", "", "", "", false) }
 
-    pub fn from_existing(plat: &crate::TileGroup) -> ModalContentElement<YaffeState> {
-        //This should never fail since we orignally got it from the database
+    pub fn from_existing(plat: &crate::TileGroup) -> QueryResult<ModalContentElement<YaffeState>> {
         let platform_id = plat.id;
-        let (path, args) = crate::data::PlatformInfo::get_info(platform_id).log_and_panic();
+        let (path, args) = crate::data::PlatformInfo::get_info(platform_id)?;
+        let provider = crate::data::PlatformInfo::get_provider(platform_id)?;
+        let extensions = crate::data::PlatformInfo::get_extensions(platform_id)?;
 
-        PlatformDetailModal::_init(platform_id, &plat.name.clone(), &path, &args, true)
+        Ok(PlatformDetailModal::_init(platform_id, &plat.name.clone(), &path, &args, &provider, &extensions, true))
     }
 
-    fn _init(platform_id: i64, name: &str, path: &str, args: &str, update: bool) -> ModalContentElement<YaffeState> {
+    fn _init(
+        platform_id: i64,
+        name: &str,
+        path: &str,
+        args: &str,
+        provider: &str,
+        extensions: &str,
+        update: bool,
+    ) -> ModalContentElement<YaffeState> {
         let name = TextBox::from("Name", name);
         let executable = TextBox::from("Executable", path);
         let args = TextBox::from("Args", args);
+        let provider = TextBox::from("Provider", provider);
+        let extensions = TextBox::from("Extensions", extensions);
 
         let mut control_map = HashMap::new();
         control_map.insert("Name".to_string(), name.get_id());
         control_map.insert("Executable".to_string(), executable.get_id());
         control_map.insert("Args".to_string(), args.get_id());
+        control_map.insert("Provider".to_string(), provider.get_id());
+        control_map.insert("Extensions".to_string(), extensions.get_id());
 
         let detail = PlatformDetailModal { control_map, platform_id, update };
         let mut modal = ModalContentElement::new(detail, true);
@@ -38,6 +52,10 @@ impl PlatformDetailModal {
             .add_child(name, ContainerSize::Shrink)
             .add_child(executable, ContainerSize::Shrink)
             .add_child(args, ContainerSize::Shrink);
+        // Metadata provider and extensions can only be chosen once the platform exists
+        if update {
+            modal.add_child(provider, ContainerSize::Shrink).add_child(extensions, ContainerSize::Shrink);
+        }
         modal.focus_first();
         modal
     }
@@ -63,6 +81,14 @@ impl ModalInputHandler<YaffeState> for PlatformDetailModal {
         }
         if args.value().is_empty() {
             validation.push("Args is required");
+        } else if crate::launch_args::validate_launch_args(&args.value()).is_err() {
+            validation.push("Args has an unterminated quote or unknown placeholder");
+        }
+        if let Some(provider) = content.find_widget(self.control_map["Provider"]) {
+            let provider = crate::convert_to!(provider, TextBox).value();
+            if !provider.is_empty() && crate::scraper::get_provider(&provider).is_none() {
+                validation.push("Unknown metadata provider");
+            }
         }
         if validation.is_empty() {
             ModalValidationResult::Ok
@@ -87,10 +113,20 @@ impl ModalInputHandler<YaffeState> for PlatformDetailModal {
 
             let exe = self.control_map["Executable"];
             let args = self.control_map["Args"];
+            let provider = self.control_map["Provider"];
+            let extensions = self.control_map["Extensions"];
             let exe = crate::convert_to!(content.find_widget(exe).unwrap(), TextBox);
             let args = crate::convert_to!(content.find_widget(args).unwrap(), TextBox);
-            crate::data::PlatformInfo::update(self.platform_id, &exe.value(), &args.value())
-                .display_failure("Unable to update platform", handler);
+            let provider = crate::convert_to!(content.find_widget(provider).unwrap(), TextBox);
+            let extensions = crate::convert_to!(content.find_widget(extensions).unwrap(), TextBox);
+            crate::data::PlatformInfo::update(
+                self.platform_id,
+                &exe.value(),
+                &args.value(),
+                &provider.value(),
+                &extensions.value(),
+            )
+            .display_failure("Unable to update platform", handler);
         }
         let name = self.control_map["Name"];
         let exe = self.control_map["Executable"];
@@ -98,7 +134,8 @@ impl ModalInputHandler<YaffeState> for PlatformDetailModal {
         let name = crate::convert_to!(content.find_widget(name).unwrap(), TextBox);
         let exe = crate::convert_to!(content.find_widget(exe).unwrap(), TextBox);
         let args = crate::convert_to!(content.find_widget(args).unwrap(), TextBox);
-        let job = crate::Job::SearchPlatform { name: name.value(), path: exe.value(), args: args.value() };
+        let providers = crate::scraper::get_provider_order(&state.settings, None);
+        let job = crate::Job::SearchPlatform { name: name.value(), path: exe.value(), args: args.value(), providers };
         state.queue.start_job(job);
 
         handler.display_toast("Searching for platform information...", 2.);
@@ -0,0 +1,101 @@
+use crate::controls::{List, TextBox};
+use crate::data::{ProfileInfo, QueryResult};
+use crate::logger::UserMessage;
+use crate::modals::{DisplayModal, ModalContentElement, ModalInputHandler, ModalSize, ModalValidationResult};
+use crate::restrictions::RestrictedAction;
+use crate::ui::{ContainerSize, UiContainer, ValueElement};
+use crate::{DeferredAction, YaffeState};
+
+const NEW_PROFILE: &str = "New Profile";
+
+/// Lists all profiles to switch to one or create a new one
+pub struct ProfilesModal {
+    profiles: Vec<ProfileInfo>,
+}
+
+impl ProfilesModal {
+    pub fn load() -> QueryResult<ModalContentElement<YaffeState>> { Ok(ProfilesModal::from(ProfileInfo::get_all()?)) }
+
+    pub fn from(profiles: Vec<ProfileInfo>) -> ModalContentElement<YaffeState> {
+        let mut items: Vec<String> = profiles.iter().map(|p| p.name.clone()).collect();
+        items.push(NEW_PROFILE.to_string());
+
+        let mut modal = ModalContentElement::new(ProfilesModal { profiles }, false);
+        modal.add_child(List::from(items), ContainerSize::Shrink);
+        modal
+    }
+}
+
+impl ModalInputHandler<YaffeState> for ProfilesModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        //Checked here as well as in the menu so no way of opening the list can leave a restricted profile
+        if !crate::modals::verify_restricted_action(state, RestrictedAction::Menu("Switch Profile")) {
+            handler.display_toast("Disable restricted mode to switch profiles", 2.);
+            return;
+        }
+
+        let list = crate::convert_to!(content.get_child(0), List<String>);
+        // Last item is always to create a new profile
+        match self.profiles.get(list.get_selected_index()) {
+            Some(profile) => switch_profile(state, profile.clone(), handler),
+            None => {
+                let mut content = ModalContentElement::new(NewProfileModal, true);
+                content.add_child(TextBox::from("Name", ""), ContainerSize::Shrink);
+                content.focus_first();
+                handler.display_modal(DisplayModal::new(NEW_PROFILE, Some("Create"), content, ModalSize::Third));
+            }
+        }
+    }
+}
+
+/// Creates a profile and switches to it
+pub struct NewProfileModal;
+
+impl ModalInputHandler<YaffeState> for NewProfileModal {
+    fn as_any(&self) -> &dyn std::any::Any { self }
+
+    fn validate(&self, content: &UiContainer<YaffeState>) -> ModalValidationResult {
+        let name = crate::convert_to!(content.get_child(0), TextBox).value();
+        if name.is_empty() {
+            ModalValidationResult::Cancel(String::from("Name is required"))
+        } else {
+            ModalValidationResult::Ok
+        }
+    }
+
+    fn on_close(
+        &self,
+        state: &mut YaffeState,
+        result: bool,
+        content: &UiContainer<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
+    ) {
+        if !result {
+            return;
+        }
+
+        let name = crate::convert_to!(content.get_child(0), TextBox).value();
+        if let Some(id) = ProfileInfo::insert(&name).display_failure("Unable to create profile", handler) {
+            switch_profile(state, ProfileInfo { id, name, ..Default::default() }, handler);
+        }
+    }
+}
+
+fn switch_profile(state: &mut YaffeState, profile: ProfileInfo, handler: &mut DeferredAction<YaffeState>) {
+    let message = format!("Playing as {}", profile.name);
+    if state.select_profile(profile).display_failure("Unable to load profile settings", handler).is_some() {
+        handler.display_toast(&message, 2.);
+    }
+}
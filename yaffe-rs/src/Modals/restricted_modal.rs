@@ -1,12 +1,57 @@
 use crate::controls::{PassBox, RestrictedPasscode};
+use crate::data::ProfileInfo;
+use crate::logger::UserMessage;
 use crate::modals::{ModalContentElement, ModalInputHandler};
+use crate::restrictions::RestrictedAction;
 use crate::ui::{ContainerSize, LayoutElement, UiContainer, ValueElement};
 use crate::{DeferredAction, YaffeState};
 
+/// Incorrect passcodes allowed before restricted mode is locked for a while
+const MAX_ATTEMPTS: i64 = 3;
+/// How long the first lockout lasts, each lockout after that is twice as long
+const LOCKOUT_SECONDS: i64 = 30;
+const MAX_LOCKOUT_DOUBLINGS: i64 = 6;
+
 pub enum RestrictedMode {
-    On(RestrictedPasscode),
+    /// Salted hash of the passcode along with its salt
+    On {
+        hash: String,
+        salt: String,
+    },
     Off,
 }
+impl RestrictedMode {
+    /// Restricted mode that was saved for a profile
+    pub fn from_profile(profile: &ProfileInfo) -> RestrictedMode {
+        if profile.passcode.is_empty() {
+            RestrictedMode::Off
+        } else {
+            RestrictedMode::On { hash: profile.passcode.clone(), salt: profile.salt.clone() }
+        }
+    }
+
+    fn enable(pass: &RestrictedPasscode) -> RestrictedMode {
+        use rand::Rng;
+        let salt = format!("{:016x}", rand::rng().random::<u64>());
+        RestrictedMode::On { hash: pass.hash(&salt), salt }
+    }
+}
+
+/// Records an incorrect passcode for a profile, locking it for longer each time too many are entered
+/// Attempts are saved with the profile so restarting doesn't allow more guesses
+fn fail_attempt(profile: &mut ProfileInfo, handler: &mut DeferredAction<YaffeState>) {
+    profile.failedattempts += 1;
+    if profile.failedattempts % MAX_ATTEMPTS == 0 {
+        let doublings = (profile.failedattempts / MAX_ATTEMPTS - 1).min(MAX_LOCKOUT_DOUBLINGS);
+        profile.lockeduntil = chrono::Utc::now().timestamp() + (LOCKOUT_SECONDS << doublings);
+    }
+    save_attempts(profile, handler);
+}
+
+fn save_attempts(profile: &ProfileInfo, handler: &mut DeferredAction<YaffeState>) {
+    let saved = ProfileInfo::set_attempts(profile.id, profile.failedattempts, profile.lockeduntil);
+    saved.display_failure("Unable to save incorrect passcodes", handler);
+}
 
 pub struct SetRestrictedModal;
 
@@ -32,27 +77,59 @@ impl ModalInputHandler<YaffeState> for SetRestrictedModal {
         content: &UiContainer<YaffeState>,
         handler: &mut DeferredAction<YaffeState>,
     ) {
-        if result {
-            let content = crate::convert_to!(content.get_child(0), PassBox);
-            let pass = content.value();
-
-            match state.restricted_mode {
-                RestrictedMode::On(p) => {
-                    if pass == p {
-                        state.restricted_mode = RestrictedMode::Off;
-                    } else {
-                        handler.display_toast("Incorrect passcode", 1.);
-                    }
+        if !result || is_locked_out(state, handler) {
+            return;
+        }
+
+        let content = crate::convert_to!(content.get_child(0), PassBox);
+        let pass = content.value();
+
+        let mode = match &state.restricted_mode {
+            RestrictedMode::On { hash, salt } => {
+                if !pass.verify(salt, hash) {
+                    fail_attempt(&mut state.profile, handler);
+                    handler.display_toast("Incorrect passcode", 1.);
+                    return;
+                }
+                if state.profile.failedattempts != 0 {
+                    state.profile.failedattempts = 0;
+                    state.profile.lockeduntil = 0;
+                    save_attempts(&state.profile, handler);
                 }
-                RestrictedMode::Off => state.restricted_mode = RestrictedMode::On(pass),
+                RestrictedMode::Off
             }
+            RestrictedMode::Off => RestrictedMode::enable(&pass),
+        };
+
+        //Only change restricted mode once it's saved so it is the same after restarting
+        let (hash, salt) = match &mode {
+            RestrictedMode::On { hash, salt } => (hash.clone(), salt.clone()),
+            RestrictedMode::Off => (String::new(), String::new()),
+        };
+        let saved = ProfileInfo::set_passcode(state.profile.id, &hash, &salt);
+        if saved.display_failure("Unable to save restricted mode", handler).is_some() {
+            state.profile.passcode = hash;
+            state.profile.salt = salt;
+            state.restricted_mode = mode;
         }
     }
 }
 
-pub fn verify_restricted_action(state: &mut YaffeState) -> bool {
-    if let RestrictedMode::On(_) = state.restricted_mode {
-        return false;
+/// Checks if an action is allowed, which is always true unless restricted mode is on
+pub fn verify_restricted_action(state: &YaffeState, action: RestrictedAction) -> bool {
+    match state.restricted_mode {
+        RestrictedMode::On { .. } => !crate::restrictions::is_restricted(state, &action),
+        RestrictedMode::Off => true,
+    }
+}
+
+/// Shows how long until a passcode can be entered if too many incorrect passcodes have been tried
+pub fn is_locked_out(state: &YaffeState, handler: &mut DeferredAction<YaffeState>) -> bool {
+    let remaining = state.profile.lockeduntil - chrono::Utc::now().timestamp();
+    if remaining > 0 {
+        let message = format!("Too many incorrect passcodes, try again in {remaining} seconds");
+        handler.display_toast(&message, 2.);
+        return true;
     }
-    true
+    false
 }
@@ -1,5 +1,6 @@
 use crate::controls::{List, ListItem};
 use crate::input::Actions;
+use crate::logger::UserMessage;
 use crate::modals::{ModalContentElement, ModalInputHandler};
 use crate::scraper::{GameScrapeResult, PlatformScrapeResult};
 use crate::ui::{AnimationManager, ContainerSize, LayoutElement, UiContainer, UiElement, WidgetId};
@@ -62,7 +63,7 @@ impl<L: ListItem + 'static> ModalInputHandler<YaffeState> for ScraperModal<L> {
         state: &mut YaffeState,
         result: bool,
         content: &UiContainer<YaffeState>,
-        _: &mut DeferredAction<YaffeState>,
+        handler: &mut DeferredAction<YaffeState>,
     ) {
         if !result {
             return;
@@ -70,11 +71,12 @@ impl<L: ListItem + 'static> ModalInputHandler<YaffeState> for ScraperModal<L> {
         if self.platform {
             let list = crate::convert_to!(content.find_widget(self.list_id).unwrap(), List<PlatformScrapeResult>);
             let item = list.get_selected();
-            crate::platform::insert_platform(state, &item.info);
+            crate::platform::insert_platform(state, &item.info).display_failure("Unable to save platform", handler);
         } else {
             let list = crate::convert_to!(content.find_widget(self.list_id).unwrap(), List<GameScrapeResult>);
             let item = list.get_selected();
-            crate::platform::insert_game(state, &item.info, item.boxart.clone());
+            let result = crate::platform::insert_game(state, &item.info, item.boxart.clone());
+            result.display_failure("Unable to save game", handler);
         }
     }
 }
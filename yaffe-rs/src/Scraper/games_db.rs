@@ -1,4 +1,4 @@
-use super::{get_null_string, GameScrapeResult, ServiceResponse, ServiceResult};
+use super::{get_null_string, GameScrapeResult, MetadataProvider, ServiceResponse, ServiceResult};
 use crate::{
     data::{GameInfo, PlatformInfo},
     scraper::PlatformScrapeResult,
@@ -7,7 +7,26 @@ use std::path::Path;
 
 const GAMESDB_API_KEY: &str = unsafe { std::str::from_utf8_unchecked(include_bytes!("../../api_key.txt")) };
 
-pub fn search_game(name: &str, exe: String, platform: i64) -> ServiceResult<ServiceResponse<GameScrapeResult>> {
+/// Retrieves game and platform information from https://thegamesdb.net
+pub struct GamesDbProvider;
+impl MetadataProvider for GamesDbProvider {
+    fn name(&self) -> &'static str { "TheGamesDB" }
+
+    fn search_game(&self, name: &str, exe: String, platform: i64) -> ServiceResult<ServiceResponse<GameScrapeResult>> {
+        search_game(name, exe, platform)
+    }
+
+    fn search_platform(
+        &self,
+        name: &str,
+        path: String,
+        args: String,
+    ) -> ServiceResult<ServiceResponse<PlatformScrapeResult>> {
+        search_platform(name, path, args)
+    }
+}
+
+fn search_game(name: &str, exe: String, platform: i64) -> ServiceResult<ServiceResponse<GameScrapeResult>> {
     crate::logger::info!("Searching for game {name}");
 
     let resp = crate::json_request!(
@@ -64,7 +83,7 @@ pub fn search_game(name: &str, exe: String, platform: i64) -> ServiceResult<Serv
     Ok(result)
 }
 
-pub fn search_platform(name: &str, path: String, args: String) -> ServiceResult<ServiceResponse<PlatformScrapeResult>> {
+fn search_platform(name: &str, path: String, args: String) -> ServiceResult<ServiceResponse<PlatformScrapeResult>> {
     crate::logger::info!("Searching for platform {name}");
 
     let resp = crate::json_request!(
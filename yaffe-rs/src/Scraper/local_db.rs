@@ -0,0 +1,243 @@
+use super::{
+    GameScrapeResult, MetadataProvider, PlatformScrapeResult, RomHashes, ServiceError, ServiceResponse, ServiceResult,
+};
+use crate::data::{GameInfo, PlatformInfo};
+use crate::logger::PanicLogEntry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+const MAX_NAME_RESULTS: usize = 10;
+// Keeps generated ids away from the ones TheGamesDB hands out
+const LOCAL_ID_OFFSET: i64 = 1_000_000_000;
+
+static CATALOGUES: LazyLock<Mutex<HashMap<PathBuf, Arc<Catalogue>>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Retrieves game information from No-Intro or MAME style DAT/XML files in the Catalogues folder
+/// Catalogues are matched to a platform by file name, eg Catalogues/Super Nintendo.dat
+pub struct LocalProvider;
+impl MetadataProvider for LocalProvider {
+    fn name(&self) -> &'static str { "Local" }
+
+    fn search_game(&self, name: &str, exe: String, platform: i64) -> ServiceResult<ServiceResponse<GameScrapeResult>> {
+        crate::logger::info!("Searching local catalogue for game {name}");
+
+        let platform_name = PlatformInfo::get_name(platform).map_err(|_| ServiceError::Other("Unknown platform"))?;
+        let Some(catalogue) = get_catalogue(&platform_name)? else {
+            return Ok(ServiceResponse::no_results());
+        };
+
+        // Files are identified exactly by their name, otherwise fall back to a name search
+        let exact = catalogue.find_by_file(&exe);
+        let entries = match exact {
+            Some(entry) => vec![entry],
+            None => catalogue.search_name(name),
+        };
+
+        let exact_index = if exact.is_some() { Some(0) } else { entries.iter().position(|e| e.name == name) };
+        let mut result = ServiceResponse::new(String::from(name), entries.len(), exact_index);
+        for entry in entries {
+            result.results.push(catalogue.to_result(entry, &exe, platform));
+        }
+
+        Ok(result)
+    }
+
+    fn identify_game(&self, hashes: &RomHashes, exe: &str, platform: i64) -> ServiceResult<Option<GameScrapeResult>> {
+        let platform_name = PlatformInfo::get_name(platform).map_err(|_| ServiceError::Other("Unknown platform"))?;
+        let Some(catalogue) = get_catalogue(&platform_name)? else {
+            return Ok(None);
+        };
+
+        let entry = [&hashes.sha1, &hashes.md5, &hashes.crc32]
+            .iter()
+            .filter(|h| !h.is_empty())
+            .find_map(|h| catalogue.find_by_hash(h));
+        Ok(entry.map(|e| catalogue.to_result(e, exe, platform)))
+    }
+
+    fn search_platform(
+        &self,
+        name: &str,
+        path: String,
+        args: String,
+    ) -> ServiceResult<ServiceResponse<PlatformScrapeResult>> {
+        crate::logger::info!("Searching local catalogues for platform {name}");
+
+        let Some(catalogue) = get_catalogue(name)? else {
+            return Ok(ServiceResponse::no_results());
+        };
+
+        let mut result = ServiceResponse::new(String::from(name), 1, Some(0));
+        let info = PlatformInfo::new(local_id(name), String::from(name), path, args);
+        result.results.push(PlatformScrapeResult {
+            info,
+            overview: catalogue.description.clone(),
+            boxart: PathBuf::new(),
+        });
+        Ok(result)
+    }
+}
+
+struct CatalogueEntry {
+    key: String,
+    name: String,
+    overview: String,
+    players: i64,
+    rating: String,
+    released: String,
+}
+
+struct Catalogue {
+    path: PathBuf,
+    description: String,
+    entries: Vec<CatalogueEntry>,
+    files: HashMap<String, usize>,
+    hashes: HashMap<String, usize>,
+}
+impl Catalogue {
+    fn load(path: PathBuf) -> ServiceResult<Catalogue> {
+        crate::logger::info!("Loading local catalogue {}", path.display());
+
+        let text = std::fs::read_to_string(&path).map_err(|_| ServiceError::Other("Unable to read catalogue"))?;
+        // DAT files usually declare a DOCTYPE
+        let options = roxmltree::ParsingOptions { allow_dtd: true, ..roxmltree::ParsingOptions::default() };
+        let doc = roxmltree::Document::parse_with_options(&text, options).map_err(|_| ServiceError::InvalidFormat)?;
+
+        let mut catalogue = Catalogue {
+            path,
+            description: String::new(),
+            entries: vec![],
+            files: HashMap::new(),
+            hashes: HashMap::new(),
+        };
+
+        for node in doc.root_element().children().filter(|n| n.is_element()) {
+            match node.tag_name().name() {
+                "header" => catalogue.description = child_text(&node, &["description", "name"]),
+                // No-Intro uses game, MAME uses machine
+                "game" | "machine" => catalogue.add_entry(&node),
+                _ => {}
+            }
+        }
+
+        Ok(catalogue)
+    }
+
+    fn add_entry(&mut self, node: &roxmltree::Node) {
+        let key = node.attribute("name").unwrap_or_default().to_string();
+        let mut name = child_text(node, &["description"]);
+        if name.is_empty() {
+            name = key.clone();
+        }
+
+        let overview = child_text(node, &["overview", "desc", "comment", "manufacturer"]);
+        let rating = child_text(node, &["rating"]);
+        let released = child_text(node, &["released", "releasedate", "year"]);
+        let players = node
+            .children()
+            .find(|n| n.has_tag_name("input"))
+            .and_then(|n| n.attribute("players").map(String::from))
+            .unwrap_or_else(|| child_text(node, &["players"]));
+        let players = players.parse::<i64>().unwrap_or(1);
+
+        let index = self.entries.len();
+        self.files.insert(key.to_lowercase(), index);
+        for rom in node.children().filter(|n| n.has_tag_name("rom")) {
+            if let Some(file) = rom.attribute("name") {
+                self.files.insert(file.to_lowercase(), index);
+            }
+            for hash in ["crc", "md5", "sha1"] {
+                if let Some(value) = rom.attribute(hash) {
+                    self.hashes.insert(value.to_lowercase(), index);
+                }
+            }
+        }
+
+        self.entries.push(CatalogueEntry { key, name, overview, players, rating, released });
+    }
+
+    fn find_by_hash(&self, hash: &str) -> Option<&CatalogueEntry> {
+        self.hashes.get(&hash.to_lowercase()).map(|i| &self.entries[*i])
+    }
+
+    fn find_by_file(&self, file: &str) -> Option<&CatalogueEntry> {
+        // Roms in subfolders are stored with their relative path, but the catalogue only has file names
+        let path = Path::new(file);
+        let file = path.file_name().map(|f| f.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let stem = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+        self.files.get(&file).or_else(|| self.files.get(&stem)).map(|i| &self.entries[*i])
+    }
+
+    fn search_name(&self, name: &str) -> Vec<&CatalogueEntry> {
+        let name = name.to_lowercase();
+        if name.is_empty() {
+            return vec![];
+        }
+        self.entries.iter().filter(|e| e.name.to_lowercase().contains(&name)).take(MAX_NAME_RESULTS).collect()
+    }
+
+    fn to_result(&self, entry: &CatalogueEntry, exe: &str, platform: i64) -> GameScrapeResult {
+        let info = GameInfo::new(
+            local_id(&entry.key),
+            entry.name.clone(),
+            entry.overview.clone(),
+            entry.players,
+            entry.rating.clone(),
+            entry.released.clone(),
+            exe.to_string(),
+            platform,
+        );
+        GameScrapeResult { info, boxart: self.get_boxart(entry) }
+    }
+
+    /// Artwork can be placed next to the catalogue in a folder of the same name, eg Catalogues/Super Nintendo/<game>.png
+    fn get_boxart(&self, entry: &CatalogueEntry) -> PathBuf {
+        let folder = self.path.with_extension("");
+        for ext in ["png", "jpg"] {
+            let path = folder.join(format!("{}.{ext}", entry.key));
+            if path.is_file() {
+                // The file may have been removed since it was checked
+                return std::fs::canonicalize(&path).unwrap_or(path);
+            }
+        }
+        PathBuf::new()
+    }
+}
+
+fn get_catalogue(platform: &str) -> ServiceResult<Option<Arc<Catalogue>>> {
+    let directory = crate::paths::catalogues();
+    if !directory.exists() {
+        return Ok(None);
+    }
+
+    let platform = crate::os::sanitize_file(platform);
+    let path = ["dat", "xml"].iter().map(|ext| directory.join(format!("{platform}.{ext}"))).find(|p| p.is_file());
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    // Catalogues can be very large so only parse them once
+    let mut catalogues = CATALOGUES.lock().log_and_panic();
+    if let Some(catalogue) = catalogues.get(&path) {
+        return Ok(Some(catalogue.clone()));
+    }
+    let catalogue = Arc::new(Catalogue::load(path.clone())?);
+    catalogues.insert(path, catalogue.clone());
+    Ok(Some(catalogue))
+}
+
+fn child_text(node: &roxmltree::Node, names: &[&str]) -> String {
+    for name in names {
+        if let Some(text) = node.children().find(|n| n.has_tag_name(*name)).and_then(|n| n.text()) {
+            let text = text.trim();
+            if !text.is_empty() {
+                return text.to_string();
+            }
+        }
+    }
+    String::new()
+}
+
+/// Ids are saved in the database so they need to come from a hash that never changes between builds
+fn local_id(value: &str) -> i64 { LOCAL_ID_OFFSET + crc32fast::hash(value.as_bytes()) as i64 }
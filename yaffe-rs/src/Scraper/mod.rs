@@ -1,6 +1,8 @@
+use crate::assets::AssetKey;
 use crate::controls::ListItem;
 use crate::data::{GameInfo, PlatformInfo};
-use crate::logger::{error, LogEntry};
+use crate::logger::{error, warn, LogEntry};
+use crate::settings::{SettingNames, SettingsFile};
 use reqwest::blocking::{Client, RequestBuilder, Response};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -12,13 +14,191 @@ pub type ServiceResult<T> = Result<T, ServiceError>;
 const GOOGLE_API_KEY: &str = unsafe { std::str::from_utf8_unchecked(include_bytes!("../../google_api_key.txt")) };
 
 mod games_db;
-pub use games_db::{search_game, search_platform};
+mod local_db;
+mod rom_hash;
+pub use games_db::GamesDbProvider;
+pub use local_db::LocalProvider;
+pub use rom_hash::{get_rom_file, hash_rom, RomHashes};
+
+/// A source of game and platform metadata
+/// Providers are looked up by name so the order they are queried in can be configured
+pub trait MetadataProvider {
+    fn name(&self) -> &'static str;
+    fn search_game(&self, name: &str, exe: String, platform: i64) -> ServiceResult<ServiceResponse<GameScrapeResult>>;
+    /// Finds a game using the checksums of its rom, if the provider supports it
+    fn identify_game(
+        &self,
+        _hashes: &RomHashes,
+        _exe: &str,
+        _platform: i64,
+    ) -> ServiceResult<Option<GameScrapeResult>> {
+        Ok(None)
+    }
+    fn search_platform(
+        &self,
+        name: &str,
+        path: String,
+        args: String,
+    ) -> ServiceResult<ServiceResponse<PlatformScrapeResult>>;
+}
+
+/// Gets the provider with the given name, if one exists
+pub fn get_provider(name: &str) -> Option<Box<dyn MetadataProvider>> {
+    let providers: Vec<Box<dyn MetadataProvider>> = vec![Box::new(GamesDbProvider), Box::new(LocalProvider)];
+    providers.into_iter().find(|p| p.name().eq_ignore_ascii_case(name))
+}
+
+/// Gets the order metadata providers should be queried in
+/// A provider set on the platform is always tried first, followed by the fallback order from settings
+pub fn get_provider_order(settings: &SettingsFile, platform: Option<i64>) -> Vec<String> {
+    let mut order = vec![];
+    if let Some(platform) = platform {
+        let provider = PlatformInfo::get_provider(platform).log("Unable to get platform metadata provider");
+        if !provider.is_empty() {
+            order.push(provider);
+        }
+    }
+
+    for name in settings.get_str(SettingNames::MetadataProviders).split(',') {
+        let name = name.trim();
+        if !name.is_empty() && !order.iter().any(|o| o.eq_ignore_ascii_case(name)) {
+            order.push(name.to_string());
+        }
+    }
+    order
+}
+
+/// How a game search is performed and how its results are applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrapeMode {
+    /// A newly found rom, exact matches are inserted without asking
+    New,
+    /// An existing game is searched again as part of its platform, the best match replaces the stored game
+    Refresh,
+    /// An existing game is searched again by name and the user always picks the result
+    Select,
+}
+
+/// Identifies a game by the hash of its rom, otherwise searches each provider in order until one returns results
+pub fn search_game(
+    providers: &[String],
+    name: &str,
+    exe: String,
+    platform: i64,
+    mode: ScrapeMode,
+) -> ServiceResult<ServiceResponse<GameScrapeResult>> {
+    let hashes = match get_rom_file(platform, &exe) {
+        Some(path) => hash_rom(&path).log("Unable to hash rom"),
+        None => RomHashes::default(),
+    };
+
+    let identified = match mode {
+        ScrapeMode::New => identify_game(providers, &hashes, &exe, platform, true),
+        // The stored game would always match itself
+        ScrapeMode::Refresh => identify_game(providers, &hashes, &exe, platform, false),
+        ScrapeMode::Select => None,
+    };
+
+    let mut result = match identified {
+        Some(game) => {
+            let mut result = ServiceResponse::new(String::from(name), 1, Some(0));
+            result.results.push(game);
+            Ok(result)
+        }
+        None => search_providers(providers, |p| p.search_game(name, exe.clone(), platform)),
+    };
+
+    if let Ok(response) = &mut result {
+        for r in response.results.iter_mut() {
+            r.info.set_hashes(&hashes);
+        }
+    }
+    result
+}
+
+fn identify_game(
+    providers: &[String],
+    hashes: &RomHashes,
+    exe: &str,
+    platform: i64,
+    check_existing: bool,
+) -> Option<GameScrapeResult> {
+    if hashes.is_empty() {
+        return None;
+    }
+
+    // The same rom may have already been scraped under a different file name
+    if check_existing {
+        if let Ok(mut info) = GameInfo::find_by_hash(platform, hashes) {
+            crate::logger::info!("Identified {exe} as existing game {}", info.name);
+            info.filename = exe.to_string();
+            return Some(GameScrapeResult { info, boxart: PathBuf::new() });
+        }
+    }
+
+    for name in providers {
+        let Some(provider) = get_provider(name) else {
+            continue;
+        };
+
+        match provider.identify_game(hashes, exe, platform) {
+            Ok(Some(game)) => {
+                crate::logger::info!("Identified {exe} as {} using {name}", game.info.name);
+                return Some(game);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Metadata provider {name} failed to identify {exe}: {e:?}"),
+        }
+    }
+    None
+}
+
+/// Searches each provider in order until one returns results
+pub fn search_platform(
+    providers: &[String],
+    name: &str,
+    path: String,
+    args: String,
+) -> ServiceResult<ServiceResponse<PlatformScrapeResult>> {
+    search_providers(providers, |p| p.search_platform(name, path.clone(), args.clone()))
+}
+
+fn search_providers<T, F>(providers: &[String], search: F) -> ServiceResult<ServiceResponse<T>>
+where
+    F: Fn(&dyn MetadataProvider) -> ServiceResult<ServiceResponse<T>>,
+{
+    let mut result = Ok(ServiceResponse::no_results());
+    for name in providers {
+        let Some(provider) = get_provider(name) else {
+            warn!("Unknown metadata provider {name}");
+            continue;
+        };
+
+        result = search(provider.as_ref());
+        match &result {
+            Ok(response) if response.count > 0 => return result,
+            Ok(_) => crate::logger::info!("No results from metadata provider {name}"),
+            Err(e) => warn!("Metadata provider {name} failed: {e:?}"),
+        }
+    }
+    result
+}
 
 #[derive(Clone)]
 pub struct GameScrapeResult {
     pub info: GameInfo,
     pub boxart: PathBuf,
 }
+impl GameScrapeResult {
+    /// Boxart can either be on a remote server or already on disk
+    pub fn boxart_key(&self) -> AssetKey {
+        if self.boxart.to_string_lossy().starts_with("http") {
+            AssetKey::Url(self.boxart.clone())
+        } else {
+            AssetKey::File(self.boxart.clone())
+        }
+    }
+}
 impl ListItem for GameScrapeResult {
     fn to_display(&self) -> String { self.info.name.clone() }
 }
@@ -158,8 +338,7 @@ pub fn check_for_updates() -> ServiceResult<bool> {
         let url = Path::new("https://www.googleapis.com/drive/v3/files/")
             .join(exe_file.unwrap())
             .join(format!("?alt=media&key={GOOGLE_API_KEY}"));
-        let file = Path::new(crate::UPDATE_FILE_PATH);
-        download_file(url, file.to_owned());
+        download_file(url, crate::paths::app_file(crate::UPDATE_FILE_NAME));
 
         return Ok(true);
     }
@@ -0,0 +1,85 @@
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Archives that can't be read, hashing the archive itself would never match a catalogue
+const UNSUPPORTED_ARCHIVES: &[&str] = &["7z", "rar"];
+
+/// Checksums of a single rom file, stored as lowercase hex strings
+#[derive(Default, Clone, Debug)]
+pub struct RomHashes {
+    pub crc32: String,
+    pub md5: String,
+    pub sha1: String,
+}
+impl RomHashes {
+    pub fn is_empty(&self) -> bool { self.crc32.is_empty() && self.md5.is_empty() && self.sha1.is_empty() }
+}
+
+/// Gets the full path of a rom file for a platform
+pub fn get_rom_file(platform: i64, file: &str) -> Option<PathBuf> {
+    let name = crate::data::PlatformInfo::get_name(platform).ok()?;
+    let path = crate::paths::roms().join(name).join(file);
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Hashes the contents of a rom file
+/// Zip archives will hash the largest file inside the archive since that is what catalogues list
+/// Other archives aren't hashed and return empty hashes
+pub fn hash_rom(path: &Path) -> std::io::Result<RomHashes> {
+    let extension = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    if UNSUPPORTED_ARCHIVES.contains(&extension.as_str()) {
+        crate::logger::info!("Skipping hashing unsupported archive {}", path.display());
+        return Ok(RomHashes::default());
+    }
+
+    crate::logger::info!("Hashing rom {}", path.display());
+    if extension == "zip" {
+        let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+        let mut largest = None;
+        let mut largest_size = 0;
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            if file.is_file() && file.size() >= largest_size {
+                largest_size = file.size();
+                largest = Some(i);
+            }
+        }
+
+        if let Some(i) = largest {
+            return hash_reader(archive.by_index(i)?);
+        }
+    }
+
+    hash_reader(File::open(path)?)
+}
+
+fn hash_reader(mut reader: impl Read) -> std::io::Result<RomHashes> {
+    let mut crc32 = crc32fast::Hasher::new();
+    let mut md5 = Md5::new();
+    let mut sha1 = Sha1::new();
+
+    // Roms can be several gigabytes so read them in chunks
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        crc32.update(&buffer[..read]);
+        md5.update(&buffer[..read]);
+        sha1.update(&buffer[..read]);
+    }
+
+    Ok(RomHashes {
+        crc32: format!("{:08x}", crc32.finalize()),
+        md5: format!("{:x}", md5.finalize()),
+        sha1: format!("{:x}", sha1.finalize()),
+    })
+}
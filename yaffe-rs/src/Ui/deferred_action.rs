@@ -4,6 +4,9 @@ use crate::ui::{WidgetId, WidgetTree};
 
 pub trait DeferredActionTrait<T> {
     fn resolve(self: Box<Self>, ui: &mut WidgetTree<T>) -> Option<DeferredAction<T>>;
+
+    /// Text shown to the user by this action, if any
+    fn message(&self) -> Option<&str> { None }
 }
 
 pub struct DeferredAction<T> {
@@ -25,6 +28,9 @@ impl<T: 'static> DeferredAction<T> {
 
     pub fn display_toast(&mut self, message: &str, time: f32) { self.actions.push(Box::new(Toast::new(message, time))) }
 
+    /// Gets the messages that would be shown to the user, for when there is no UI to resolve actions in
+    pub fn messages(&self) -> Vec<&str> { self.actions.iter().filter_map(|a| a.message()).collect() }
+
     pub fn resolve(self, ui: &mut WidgetTree<T>) {
         let mut queue = self.actions;
 
@@ -97,4 +103,6 @@ impl<T> DeferredActionTrait<T> for DisplayMessageAction {
         display_error(ui, self.message);
         None
     }
+
+    fn message(&self) -> Option<&str> { Some(&self.message) }
 }
@@ -1,6 +1,7 @@
 use crate::logger::UserMessage;
 use crate::modals::InfoModal;
 use crate::modals::{DisplayModal, ModalSize};
+use crate::restrictions::RestrictedAction;
 use crate::state::GroupType;
 use crate::ui::{
     get_drawable_text, AnimationManager, LayoutElement, LoadPluginAction, RevertFocusAction, UiElement, WidgetId,
@@ -54,7 +55,7 @@ impl UiElement<YaffeState> for AppList {
             }
             Actions::Accept => {
                 if let Some(exe) = state.get_selected_tile() {
-                    if !exe.restricted || crate::modals::verify_restricted_action(state) {
+                    if crate::modals::verify_restricted_action(state, RestrictedAction::Launch(exe)) {
                         start_app(state, handler)
                     }
                 }
@@ -62,8 +63,13 @@ impl UiElement<YaffeState> for AppList {
             }
             Actions::Info => {
                 if let Some(exe) = state.get_selected_tile() {
-                    let info = InfoModal::from(exe);
-                    handler.display_modal(DisplayModal::new(&exe.name.clone(), None, info, ModalSize::Half));
+                    // Only games from the database can be edited, and not while restricted mode could prevent it
+                    let editable = state.get_selected_group().kind.is_game_list()
+                        && matches!(exe.tile_type, TileType::App)
+                        && crate::modals::verify_restricted_action(state, RestrictedAction::Menu("Edit Game"));
+                    let info = InfoModal::from(exe, editable);
+                    let confirm = if editable { Some("Edit") } else { None };
+                    handler.display_modal(DisplayModal::new(&exe.name.clone(), confirm, info, ModalSize::Half));
                 }
                 true
             }
@@ -71,6 +77,25 @@ impl UiElement<YaffeState> for AppList {
                 handler.focus_widget(crate::SEARCH_BAR_ID);
                 true
             }
+            Actions::ToggleFavorite => {
+                if let Some(exe) = state.get_selected_tile() {
+                    if state.get_selected_group().kind.is_game_list() && matches!(exe.tile_type, TileType::App) {
+                        let favorite = !exe.favorite;
+                        let message = if favorite {
+                            format!("Added {} to favorites", exe.name)
+                        } else {
+                            format!("Removed {} from favorites", exe.name)
+                        };
+
+                        let result = crate::data::GameInfo::set_favorite(exe.group_id, &exe.file, favorite);
+                        if result.display_failure("Unable to update favorite", handler).is_some() {
+                            handler.display_toast(&message, 2.);
+                            state.refresh_list = true;
+                        }
+                    }
+                }
+                true
+            }
             Actions::Back => {
                 handler.defer(RevertFocusAction);
                 true
@@ -354,7 +379,9 @@ fn start_app(state: &mut YaffeState, handler: &mut DeferredAction<YaffeState>) {
             TileType::Folder => {
                 state.navigate_to(tile);
                 match tile.get_containing_group_type(state) {
-                    GroupType::Recents => unreachable!(),
+                    GroupType::Recents | GroupType::MostPlayed | GroupType::Favorites | GroupType::Collection(_) => {
+                        unreachable!()
+                    }
                     GroupType::Emulator => unimplemented!(),
                     GroupType::Plugin(_) => handler.defer(LoadPluginAction(true)),
                 }
@@ -1,5 +1,8 @@
+use crate::assets::Images;
 use crate::controls::MENU_BACKGROUND;
+use crate::logger::UserMessage;
 use crate::modals::{DisplayModal, PlatformDetailModal};
+use crate::restrictions::RestrictedAction;
 use crate::ui::{AnimationManager, LayoutElement, LoadPluginAction, UiElement, WidgetId, MARGIN};
 use crate::{
     state::GroupType, widget, Actions, DeferredAction, LogicalPosition, LogicalSize, Rect, ScaleFactor, YaffeState,
@@ -34,13 +37,20 @@ impl UiElement<YaffeState> for PlatformList {
             Actions::Info => {
                 let group = state.get_selected_group();
                 if group.kind.allow_edit() {
+                    if !crate::modals::verify_restricted_action(state, RestrictedAction::Menu("Edit Platform")) {
+                        handler.display_toast("Edit Platform is locked by restricted mode", 2.);
+                        return true;
+                    }
+
                     let modal = PlatformDetailModal::from_existing(group);
-                    handler.display_modal(DisplayModal::new(
-                        "Platform Info",
-                        Some("Save"),
-                        modal,
-                        crate::modals::ModalSize::Third,
-                    ));
+                    if let Some(modal) = modal.display_failure("Unable to load platform", handler) {
+                        handler.display_modal(DisplayModal::new(
+                            "Platform Info",
+                            Some("Save"),
+                            modal,
+                            crate::modals::ModalSize::Third,
+                        ));
+                    }
                 }
                 true
             }
@@ -69,12 +79,13 @@ impl UiElement<YaffeState> for PlatformList {
         let selected_index = state.selected.group_index();
         let right = rect.right();
         let mut y = 10.;
-        let mut plat_kind: Option<GroupType> = None;
+        let mut plat_header: Option<Images> = None;
         for (i, p) in state.groups.iter().enumerate() {
             //Header for the specific platform type
-            if plat_kind.is_none() || std::mem::discriminant(&plat_kind.unwrap()) != std::mem::discriminant(&p.kind) {
-                y = draw_header(graphics, y, rect.width(), p.kind);
-                plat_kind = Some(p.kind);
+            let header = get_header_image(p.kind);
+            if plat_header != Some(header) {
+                y = draw_header(graphics, y, rect.width(), header);
+                plat_header = Some(header);
             }
 
             let name_label =
@@ -106,13 +117,17 @@ impl UiElement<YaffeState> for PlatformList {
     }
 }
 
-fn draw_header(graphics: &mut crate::Graphics, y: f32, width: f32, kind: GroupType) -> f32 {
+fn get_header_image(kind: GroupType) -> Images {
+    match kind {
+        GroupType::Emulator => Images::Emulator,
+        GroupType::Plugin(_) => Images::App,
+        // Recents, favorites and collections are shown together under one header
+        GroupType::Recents | GroupType::MostPlayed | GroupType::Favorites | GroupType::Collection(_) => Images::Recent,
+    }
+}
+
+fn draw_header(graphics: &mut crate::Graphics, y: f32, width: f32, image: Images) -> f32 {
     const ICON_SIZE: f32 = 28.;
-    let image = match kind {
-        GroupType::Emulator => crate::assets::Images::Emulator,
-        GroupType::Plugin(_) => crate::assets::Images::App,
-        GroupType::Recents => crate::assets::Images::Recent,
-    };
 
     let y = y + MARGIN * 2.;
     graphics.draw_image(
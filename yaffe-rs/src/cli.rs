@@ -0,0 +1,266 @@
+use crate::job_system::{Job, JobResult};
+use crate::logger::UserMessage;
+use crate::settings::SettingsFile;
+use crate::{DeferredAction, YaffeState};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use winit::window::WindowId;
+
+const USAGE: &str = "Usage:
+    yaffe export <file.json|file.csv>
+    yaffe import <file.json|file.csv>
+    yaffe export-gamelist <platform> <gamelist.xml>
+    yaffe import-gamelist <platform> <gamelist.xml>
+    yaffe platform list
+    yaffe platform add <name> <executable> [args]
+    yaffe scan
+    yaffe games list [platform]
+    yaffe settings list
+    yaffe settings set <name> <value>
+
+Any command can be given --data-dir <folder> to use a different data folder";
+
+/// Runs a command given on the command line without starting the UI, returning the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    match args.as_slice() {
+        ["export", file] => match crate::library::export_library(Path::new(file)) {
+            Ok(count) => {
+                println!("Exported {count} games to {file}");
+                0
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                1
+            }
+        },
+        ["import", file] => match crate::library::import_library(Path::new(file)) {
+            Ok(count) => {
+                println!("Imported {count} games from {file}");
+                0
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                1
+            }
+        },
+        ["export-gamelist", platform, file] => match crate::gamelist::export_gamelist(platform, Path::new(file)) {
+            Ok(count) => {
+                println!("Exported {count} games to {file}");
+                0
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                1
+            }
+        },
+        ["import-gamelist", platform, file] => match crate::gamelist::import_gamelist(platform, Path::new(file)) {
+            Ok(count) => {
+                println!("Imported {count} games from {file}");
+                0
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                1
+            }
+        },
+        ["platform", "list"] => list_platforms(),
+        ["platform", "add", name, exe] => add_platform(name, exe, ""),
+        ["platform", "add", name, exe, args] => add_platform(name, exe, args),
+        ["scan"] => scan(),
+        ["games", "list"] => list_games(None),
+        ["games", "list", platform] => list_games(Some(platform)),
+        ["settings", "list"] => list_settings(),
+        ["settings", "set", name, value] => set_setting(name, value),
+        _ => {
+            eprintln!("{USAGE}");
+            1
+        }
+    }
+}
+
+fn list_platforms() -> i32 {
+    match crate::data::PlatformInfo::get_all() {
+        Ok(platforms) => {
+            for p in platforms {
+                println!("{}\t{}\t{}", p.platform, p.path, p.args);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Unable to get platforms: {e}");
+            1
+        }
+    }
+}
+
+/// Searches for the platform the same way adding an emulator in the UI does
+fn add_platform(name: &str, exe: &str, args: &str) -> i32 {
+    let mut headless = Headless::new();
+    let providers = crate::scraper::get_provider_order(&headless.state.settings, None);
+    let job = Job::SearchPlatform { name: name.to_string(), path: exe.to_string(), args: args.to_string(), providers };
+    headless.state.queue.start_job(job);
+
+    if headless.run_jobs() == 0 {
+        return 1;
+    }
+    0
+}
+
+/// Finds new roms and searches for their information the same way scanning from the UI does
+fn scan() -> i32 {
+    let mut headless = Headless::new();
+    crate::platform::get_database_info(&mut headless.state, &mut headless.handler);
+    crate::platform::scan_new_files(&mut headless.state, &mut headless.handler);
+    headless.print_messages();
+
+    let count = headless.run_jobs();
+    println!("Added {count} games");
+    0
+}
+
+fn list_games(platform: Option<&str>) -> i32 {
+    let platforms = match platform {
+        Some(name) => crate::library::find_platform(name).map(|p| vec![p]),
+        None => crate::data::PlatformInfo::get_all().map_err(crate::library::LibraryError::from),
+    };
+
+    let result = platforms.and_then(|platforms| {
+        for p in platforms {
+            for g in crate::data::GameInfo::get_all(p.id)? {
+                let missing = if g.missing { "\t(missing)" } else { "" };
+                println!("{}\t{}\t{}{missing}", p.platform, g.filename, g.name);
+            }
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn list_settings() -> i32 {
+    let settings =
+        crate::settings::load_settings(crate::paths::settings(), true).unwrap_or_else(|_| SettingsFile::default());
+    for (name, value) in settings.get_full_settings() {
+        println!("{name} = {value}");
+    }
+    0
+}
+
+fn set_setting(name: &str, value: &str) -> i32 {
+    if !SettingsFile::default().get_full_settings().iter().any(|(n, _)| n == name) {
+        eprintln!("Unknown setting {name}");
+        return 1;
+    }
+
+    // Settings can only be loaded from a file that exists
+    let path = crate::paths::settings();
+    if !path.exists() {
+        if let Err(e) = std::fs::write(&path, "") {
+            eprintln!("Unable to create settings file: {e}");
+            return 1;
+        }
+    }
+
+    let result = crate::settings::load_settings(crate::paths::settings(), true).and_then(|mut settings| {
+        settings.set_setting(name, value)?;
+        settings.serialize()?;
+        Ok(())
+    });
+    match result {
+        Ok(()) => {
+            println!("Set {name} to {value}");
+            0
+        }
+        Err(e) => {
+            eprintln!("Unable to set {name}: {e:?}");
+            1
+        }
+    }
+}
+
+/// Runs the parts of Yaffe that are normally driven by the UI on the current thread
+struct Headless {
+    state: YaffeState,
+    handler: DeferredAction<YaffeState>,
+    jobs: spmc::Receiver<(Option<WindowId>, Job)>,
+}
+impl Headless {
+    fn new() -> Headless {
+        let settings = match crate::settings::load_settings(crate::paths::settings(), true) {
+            Ok(settings) => settings,
+            Err(e) => {
+                crate::logger::error!("Unable to load settings: {e:?}");
+                SettingsFile::default()
+            }
+        };
+
+        let (queue, jobs) = crate::job_system::start_local_queue();
+        let rom_watcher = crate::rom_watcher::RomWatcher::disabled();
+        let state = YaffeState::new(Rc::new(RefCell::new(None)), settings, queue, rom_watcher);
+        Headless { state, handler: DeferredAction::new(), jobs }
+    }
+
+    /// Prints the messages that would have been displayed in the UI
+    fn print_messages(&mut self) {
+        for message in self.handler.messages() {
+            println!("{message}");
+        }
+        self.handler = DeferredAction::new();
+    }
+
+    /// Runs every queued job, including any jobs they queue, and saves exact search matches
+    /// Returns how many games or platforms were saved
+    fn run_jobs(&mut self) -> usize {
+        let mut saved = 0;
+        while let Ok((_, job)) = self.jobs.try_recv() {
+            match crate::job_system::run_job(job) {
+                Some(JobResult::SearchGame(Ok(result), _)) => match result.get_exact() {
+                    Some(game) => {
+                        let info = &game.info;
+                        let inserted = crate::platform::insert_game(&mut self.state, info, game.boxart.clone());
+                        if inserted
+                            .display_failure(&format!("Unable to save {}", info.name), &mut self.handler)
+                            .is_some()
+                        {
+                            println!("Added {}", info.name);
+                            saved += 1;
+                        }
+                    }
+                    None => println!("No exact match found for {}, skipping", result.request),
+                },
+                Some(JobResult::SearchPlatform(Ok(result))) => match result.get_exact() {
+                    Some(platform) => {
+                        let info = &platform.info;
+                        let inserted = crate::platform::insert_platform(&mut self.state, info);
+                        if inserted.display_failure("Unable to save platform", &mut self.handler).is_some() {
+                            println!("Added platform {}", info.platform);
+                            saved += 1;
+                        }
+                    }
+                    None => {
+                        let names: Vec<&str> = result.results.iter().map(|p| p.info.platform.as_str()).collect();
+                        println!(
+                            "No exact match found for {}, similar platforms: {}",
+                            result.request,
+                            names.join(", ")
+                        );
+                    }
+                },
+                Some(JobResult::SearchGame(Err(e), _)) | Some(JobResult::SearchPlatform(Err(e))) => {
+                    println!("Error occured while searching: {e:?}")
+                }
+                _ => {}
+            }
+            self.print_messages();
+        }
+        saved
+    }
+}
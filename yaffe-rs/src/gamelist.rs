@@ -0,0 +1,226 @@
+use crate::data::{GameInfo, Transaction};
+use crate::library::{find_platform, LibraryResult};
+use crate::logger::LogEntry;
+use std::path::{Path, PathBuf};
+
+/// Reads the games of an EmulationStation gamelist.xml in to a platform, returning how many were imported
+/// Existing games are matched by their rom path and have their metadata replaced
+/// EmulationStation ratings are a score rather than an age rating so they aren't imported
+pub fn import_gamelist(platform: &str, path: &Path) -> LibraryResult<usize> {
+    let platform = find_platform(platform)?;
+    let rom_path = std::fs::canonicalize(crate::paths::roms().join(&platform.platform)).unwrap_or_default();
+    // Images are relative to the folder the gamelist is in
+    let folder = path.parent().unwrap_or(Path::new(""));
+
+    let text = std::fs::read_to_string(path)?;
+    let doc = roxmltree::Document::parse(&text)?;
+
+    // Nothing is imported if any game fails
+    let transaction = Transaction::begin()?;
+    let mut count = 0;
+    for node in doc.root_element().children().filter(|n| n.has_tag_name("game")) {
+        let file = get_rom_file(&child_text(&node, "path"), &rom_path);
+        if file.is_empty() {
+            continue;
+        }
+
+        let mut name = child_text(&node, "name");
+        if name.is_empty() {
+            name = Path::new(&file).file_stem().unwrap_or_default().to_string_lossy().to_string();
+        }
+        let overview = child_text(&node, "desc");
+        let players = parse_players(&child_text(&node, "players"));
+        let released = parse_release_date(&child_text(&node, "releasedate"));
+
+        let old_name = match GameInfo::get(platform.id, &file) {
+            Ok(mut game) => {
+                let old_name = std::mem::replace(&mut game.name, name.clone());
+                game.overview = overview;
+                game.players = players;
+                game.released = released;
+                GameInfo::update(&game)?;
+                Some(old_name)
+            }
+            Err(_) => {
+                let game =
+                    GameInfo::new(0, name.clone(), overview, players, String::new(), released, file, platform.id);
+                GameInfo::insert(&game)?;
+                None
+            }
+        };
+
+        let boxart = crate::assets::get_asset_path(&platform.platform, &name);
+        let image = child_text(&node, "image");
+        let image = resolve_path(folder, &image);
+        if !image.as_os_str().is_empty() && image.is_file() {
+            std::fs::copy(&image, &boxart).log("Unable to copy boxart");
+        } else if let Some(old_name) = old_name.filter(|n| *n != name) {
+            // Boxart is stored by game name so it needs to follow a rename
+            let old_boxart = crate::assets::get_asset_path(&platform.platform, &old_name);
+            if old_boxart.exists() {
+                std::fs::rename(old_boxart, boxart).log("Unable to rename boxart");
+            }
+        }
+
+        count += 1;
+    }
+
+    transaction.commit()?;
+    Ok(count)
+}
+
+/// Writes the games of a platform to an EmulationStation gamelist.xml, returning how many were exported
+pub fn export_gamelist(platform: &str, path: &Path) -> LibraryResult<usize> {
+    let platform = find_platform(platform)?;
+    let games = GameInfo::get_all(platform.id)?;
+
+    let mut xml = String::from("<?xml version=\"1.0\"?>\n<gameList>\n");
+    for g in &games {
+        xml.push_str("\t<game>\n");
+        push_element(&mut xml, "path", &format!("./{}", g.filename));
+        push_element(&mut xml, "name", &g.name);
+        push_element(&mut xml, "desc", &g.overview);
+
+        let boxart = crate::assets::get_asset_path(&platform.platform, &g.name);
+        if let Ok(boxart) = std::fs::canonicalize(boxart) {
+            push_element(&mut xml, "image", &boxart.to_string_lossy());
+        }
+        if let Some(released) = format_release_date(&g.released) {
+            push_element(&mut xml, "releasedate", &released);
+        }
+        if g.players > 1 {
+            push_element(&mut xml, "players", &format!("1-{}", g.players));
+        } else if g.players == 1 {
+            push_element(&mut xml, "players", "1");
+        }
+        xml.push_str("\t</game>\n");
+    }
+    xml.push_str("</gameList>\n");
+
+    std::fs::write(path, xml)?;
+    Ok(games.len())
+}
+
+/// Gets a rom path relative to the platform folder, like games are stored in the database
+fn get_rom_file(path: &str, rom_path: &Path) -> String {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        match path.strip_prefix(rom_path) {
+            Ok(_) => crate::rom_scan::get_relative_path(rom_path, path),
+            // Roms outside of the platform folder can only be matched by name
+            Err(_) => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        }
+    } else {
+        let path = path.strip_prefix("./").unwrap_or(path);
+        crate::rom_scan::get_relative_path(Path::new(""), path)
+    }
+}
+
+fn resolve_path(folder: &Path, path: &str) -> PathBuf {
+    if path.is_empty() {
+        return PathBuf::new();
+    }
+
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        folder.join(path.strip_prefix("./").unwrap_or(path))
+    }
+}
+
+/// Players are given as a range like 1-4, we only store the maximum
+fn parse_players(players: &str) -> i64 {
+    players.rsplit('-').next().and_then(|p| p.trim().parse::<i64>().ok()).unwrap_or(1)
+}
+
+/// Converts 19910821T000000 to 1991-08-21
+fn parse_release_date(date: &str) -> String {
+    match date.get(0..8) {
+        Some(digits) if digits.chars().all(|c| c.is_ascii_digit()) => {
+            format!("{}-{}-{}", &digits[0..4], &digits[4..6], &digits[6..8])
+        }
+        _ => date.to_string(),
+    }
+}
+
+/// Converts 1991-08-21, or just a year, to 19910821T000000
+fn format_release_date(date: &str) -> Option<String> {
+    let digits: String = date.chars().filter(|c| c.is_ascii_digit()).collect();
+    match digits.len() {
+        8 => Some(format!("{digits}T000000")),
+        4 => Some(format!("{digits}0101T000000")),
+        _ => None,
+    }
+}
+
+fn push_element(xml: &mut String, name: &str, value: &str) {
+    let value = value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;");
+    xml.push_str(&format!("\t\t<{name}>{value}</{name}>\n"));
+}
+
+fn child_text(node: &roxmltree::Node, name: &str) -> String {
+    node.children().find(|n| n.has_tag_name(name)).and_then(|n| n.text()).unwrap_or_default().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn players() {
+        assert_eq!(parse_players("1-4"), 4);
+        assert_eq!(parse_players("1 - 2"), 2);
+        assert_eq!(parse_players("2"), 2);
+        assert_eq!(parse_players(""), 1);
+        assert_eq!(parse_players("unknown"), 1);
+    }
+
+    #[test]
+    fn release_dates() {
+        assert_eq!(parse_release_date("19910821T000000"), "1991-08-21");
+        assert_eq!(parse_release_date("19910821"), "1991-08-21");
+        assert_eq!(parse_release_date("1991"), "1991");
+        assert_eq!(parse_release_date("not a date"), "not a date");
+        assert_eq!(parse_release_date(""), "");
+
+        assert_eq!(format_release_date("1991-08-21").as_deref(), Some("19910821T000000"));
+        assert_eq!(format_release_date("1991").as_deref(), Some("19910101T000000"));
+        assert_eq!(format_release_date("21st of August"), None);
+        assert_eq!(format_release_date(""), None);
+    }
+
+    #[test]
+    fn release_dates_round_trip() {
+        let date = parse_release_date("19910821T000000");
+        assert_eq!(format_release_date(&date).as_deref(), Some("19910821T000000"));
+    }
+
+    #[test]
+    fn rom_files() {
+        let rom_path = std::env::temp_dir().join("roms").join("SNES");
+        let inside = rom_path.join("Hacks").join("Game.sfc");
+        let outside = std::env::temp_dir().join("other").join("Game.sfc");
+
+        assert_eq!(get_rom_file("./Hacks/Game.sfc", &rom_path), "Hacks/Game.sfc");
+        assert_eq!(get_rom_file("Game.sfc", &rom_path), "Game.sfc");
+        assert_eq!(get_rom_file(&inside.to_string_lossy(), &rom_path), "Hacks/Game.sfc");
+        assert_eq!(get_rom_file(&outside.to_string_lossy(), &rom_path), "Game.sfc");
+    }
+
+    #[test]
+    fn elements_are_escaped() {
+        let mut xml = String::new();
+        push_element(&mut xml, "name", "Tom & Jerry's <\"Adventure\">");
+        assert_eq!(xml, "\t\t<name>Tom &amp; Jerry&apos;s &lt;&quot;Adventure&quot;&gt;</name>\n");
+
+        let doc = roxmltree::Document::parse(&format!("<game>{}</game>", xml.trim())).unwrap();
+        assert_eq!(child_text(&doc.root_element(), "name"), "Tom & Jerry's <\"Adventure\">");
+        assert_eq!(child_text(&doc.root_element(), "desc"), "");
+    }
+}
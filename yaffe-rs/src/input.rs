@@ -36,6 +36,7 @@ pub enum Actions {
     Left,
     Right,
     Filter,
+    ToggleFavorite,
     ToggleOverlay,
     ShowMenu,
     KeyPress(InputType),
@@ -66,6 +67,7 @@ pub fn get_input_map() -> InputMap<KeyCode, ControllerInput, Actions> {
     let mut m = InputMap::new();
     m.insert(KeyCode::Digit1, ControllerInput::ButtonWest, Actions::Info);
     m.insert(KeyCode::Digit2, ControllerInput::ButtonNorth, Actions::Filter);
+    m.insert(KeyCode::Digit3, ControllerInput::ButtonBack, Actions::ToggleFavorite);
     m.insert(KeyCode::Enter, ControllerInput::ButtonSouth, Actions::Accept);
     m.insert(KeyCode::Escape, ControllerInput::ButtonEast, Actions::Back);
     m.insert(KeyCode::ArrowUp, ControllerInput::DirectionUp, Actions::Up);
@@ -48,36 +48,58 @@ pub fn start_job_system() -> (ThreadSafeJobQueue, JobResults) {
     (ThreadSafeJobQueue::new(tx), notify_rx)
 }
 
-fn poll_pending_jobs(queue: spmc::Receiver<(Option<WindowId>, Job)>, notify: Sender<(Option<WindowId>, JobResult)>) {
-    let send_reply = |window_id, result| {
-        notify.send((window_id, result)).log("Unable to notify main loop about finished job");
-    };
+/// Creates a job queue that isn't processed by any threads
+/// Jobs sent to it are run on the current thread by receiving them and passing them to `run_job`
+pub fn start_local_queue() -> (ThreadSafeJobQueue, spmc::Receiver<(Option<WindowId>, Job)>) {
+    let (tx, rx) = spmc::channel();
+    (ThreadSafeJobQueue::new(tx), rx)
+}
 
+fn poll_pending_jobs(queue: spmc::Receiver<(Option<WindowId>, Job)>, notify: Sender<(Option<WindowId>, JobResult)>) {
     while let Ok((window_id, msg)) = queue.recv() {
-        crate::logger::trace!("Processing job {msg:?}");
-        match msg {
-            Job::LoadImage { key, file } => {
-                if let Some((data, dimensions)) = crate::assets::load_image_async(&key, file) {
-                    send_reply(window_id, JobResult::LoadImage { data, dimensions, key });
-                }
-            }
+        if let Some(result) = run_job(msg) {
+            notify.send((window_id, result)).log("Unable to notify main loop about finished job");
+        }
+    }
+}
 
-            Job::DownloadUrl { url, file_path } => crate::scraper::download_file(url, file_path),
+/// Runs a job on the current thread, returning its result if it has one
+pub fn run_job(msg: Job) -> Option<JobResult> {
+    crate::logger::trace!("Processing job {msg:?}");
+    match msg {
+        Job::LoadImage { key, file } => crate::assets::load_image_async(&key, file)
+            .map(|(data, dimensions)| JobResult::LoadImage { data, dimensions, key }),
 
-            Job::SearchPlatform { name, path, args } => {
-                let result = search_platform(&name, path, args);
-                send_reply(window_id, JobResult::SearchPlatform(result));
-            }
+        Job::DownloadUrl { url, file_path } => {
+            crate::scraper::download_file(url, file_path);
+            None
+        }
 
-            Job::SearchGame { exe, name, platform } => {
-                let result = search_game(&name, exe, platform);
-                send_reply(window_id, JobResult::SearchGame(result));
-            }
+        Job::SearchPlatform { name, path, args, providers } => {
+            Some(JobResult::SearchPlatform(search_platform(&providers, &name, path, args)))
+        }
 
-            Job::CheckUpdates => {
-                let applied = crate::scraper::check_for_updates().log("Error checking for updates");
-                send_reply(window_id, JobResult::CheckUpdates(applied))
+        Job::SearchGame { exe, name, platform, providers, mode } => {
+            let result = search_game(&providers, &name, exe, platform, mode);
+            Some(JobResult::SearchGame(result, mode))
+        }
+
+        Job::HashRom { platform, file } => {
+            if let Some(path) = get_rom_file(platform, &file) {
+                match hash_rom(&path) {
+                    Ok(hashes) if !hashes.is_empty() => {
+                        crate::data::GameInfo::update_hashes(platform, &file, &hashes).log("Unable to save rom hashes")
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Unable to hash rom {file}: {e:?}"),
+                }
             }
+            None
+        }
+
+        Job::CheckUpdates => {
+            let applied = crate::scraper::check_for_updates().log("Error checking for updates");
+            Some(JobResult::CheckUpdates(applied))
         }
     }
 }
@@ -96,18 +118,27 @@ pub enum Job {
         file_path: std::path::PathBuf,
     },
 
-    /// Searches TheGamesDb for a given platform
+    /// Searches metadata providers, in order, for a given platform
     SearchPlatform {
         name: String,
         path: String,
         args: String,
+        providers: Vec<String>,
     },
 
-    /// Searches TheGamesDb for a given game
+    /// Searches metadata providers, in order, for a given game
     SearchGame {
         exe: String,
         name: String,
         platform: i64,
+        providers: Vec<String>,
+        mode: ScrapeMode,
+    },
+
+    /// Calculates and stores the hashes of a rom that is already in the database
+    HashRom {
+        platform: i64,
+        file: String,
     },
 
     CheckUpdates,
@@ -116,6 +147,6 @@ pub enum Job {
 pub enum JobResult {
     LoadImage { data: Vec<u8>, dimensions: (u32, u32), key: AssetKey },
     SearchPlatform(ServiceResult<ServiceResponse<PlatformScrapeResult>>),
-    SearchGame(ServiceResult<ServiceResponse<GameScrapeResult>>),
+    SearchGame(ServiceResult<ServiceResponse<GameScrapeResult>>, ScrapeMode),
     CheckUpdates(bool),
 }
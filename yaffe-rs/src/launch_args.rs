@@ -0,0 +1,218 @@
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum LaunchArgsError {
+    UnterminatedQuote,
+    UnterminatedPlaceholder,
+    UnknownPlaceholder(String),
+}
+impl std::fmt::Display for LaunchArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LaunchArgsError::UnterminatedQuote => write!(f, "Launch arguments contain an unterminated quote"),
+            LaunchArgsError::UnterminatedPlaceholder => {
+                write!(f, "Launch arguments contain an unterminated placeholder")
+            }
+            LaunchArgsError::UnknownPlaceholder(name) => write!(f, "Unknown launch argument placeholder {{{name}}}"),
+        }
+    }
+}
+impl std::error::Error for LaunchArgsError {}
+
+/// Builds the arguments used to launch a rom from a platform or game args template
+///
+/// Args are split like a shell would, so values with spaces can be wrapped in single or double quotes,
+/// eg `-L cores/snes.so "{rom}" --fullscreen`. The following placeholders are replaced in each argument:
+/// - `{rom}` full path to the rom
+/// - `{rom_stem}` file name of the rom without its extension
+/// - `{platform}` name of the platform
+/// - `{yaffe_dir}` directory Yaffe is installed to
+///
+/// Use `{{` and `}}` for literal braces. Templates without `{rom}` get the rom path as the first argument
+pub fn build_launch_args(template: &str, rom: &Path, platform: &str) -> Result<Vec<String>, LaunchArgsError> {
+    let yaffe_dir = crate::paths::app_dir();
+    let values = [
+        ("rom", rom.to_string_lossy().to_string()),
+        ("rom_stem", rom.file_stem().unwrap_or_default().to_string_lossy().to_string()),
+        ("platform", platform.to_string()),
+        ("yaffe_dir", yaffe_dir.to_string_lossy().to_string()),
+    ];
+
+    let mut args = vec![];
+    let mut has_rom = false;
+    for arg in split_args(template)? {
+        args.push(replace_placeholders(&arg, &values, &mut has_rom)?);
+    }
+
+    // Args from before templates were supported expect the rom to come first
+    if !has_rom {
+        args.insert(0, values[0].1.clone());
+    }
+    Ok(args)
+}
+
+/// Checks that an args template can be used to launch a rom
+pub fn validate_launch_args(template: &str) -> Result<(), LaunchArgsError> {
+    build_launch_args(template, Path::new(""), "").map(|_| ())
+}
+
+/// Splits a string in to arguments on whitespace, respecting quotes and backslash escapes
+/// Backslashes only escape quotes and whitespace so Windows paths don't need to be doubled up
+fn split_args(args: &str) -> Result<Vec<String>, LaunchArgsError> {
+    let mut result = vec![];
+    let mut current = String::new();
+    // Quoted empty strings are still an argument
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+
+    let mut chars = args.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            // Everything is literal inside single quotes
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => current.push(c),
+
+            (Some('"'), '"') => quote = None,
+            (Some('"'), '\\') => match chars.next() {
+                Some('"') => current.push('"'),
+                Some(next) => {
+                    current.push('\\');
+                    current.push(next);
+                }
+                None => return Err(LaunchArgsError::UnterminatedQuote),
+            },
+            (Some(_), _) => current.push(c),
+
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (None, '\\') => {
+                match chars.next() {
+                    Some(next) if next == '"' || next == '\'' || next.is_whitespace() => current.push(next),
+                    Some(next) => {
+                        current.push('\\');
+                        current.push(next);
+                    }
+                    None => current.push('\\'),
+                }
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    result.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, _) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(LaunchArgsError::UnterminatedQuote);
+    }
+    if in_arg {
+        result.push(current);
+    }
+    Ok(result)
+}
+
+fn replace_placeholders(arg: &str, values: &[(&str, String)], has_rom: &mut bool) -> Result<String, LaunchArgsError> {
+    let mut result = String::new();
+    let mut chars = arg.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(LaunchArgsError::UnterminatedPlaceholder),
+                    }
+                }
+
+                *has_rom |= name == "rom";
+                match values.iter().find(|(n, _)| *n == name) {
+                    Some((_, value)) => result.push_str(value),
+                    None => return Err(LaunchArgsError::UnknownPlaceholder(name)),
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Vec<(&'static str, String)> {
+        vec![("rom", String::from("/roms/Super Mario.sfc")), ("platform", String::from("SNES"))]
+    }
+
+    #[test]
+    fn quoted_paths_with_spaces() {
+        let args = split_args(r#"-L "cores/snes core.so" '/roms/My Game.sfc' --fullscreen"#).unwrap();
+        assert_eq!(args, vec!["-L", "cores/snes core.so", "/roms/My Game.sfc", "--fullscreen"]);
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        let args = split_args(r#""say \"hi\"" it\'s C:\roms\game"#).unwrap();
+        assert_eq!(args, vec![r#"say "hi""#, "it's", r"C:\roms\game"]);
+    }
+
+    #[test]
+    fn empty_quotes_are_an_argument() {
+        let args = split_args(r#"-a "" -b"#).unwrap();
+        assert_eq!(args, vec!["-a", "", "-b"]);
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert!(matches!(split_args(r#"-L "cores/snes.so"#), Err(LaunchArgsError::UnterminatedQuote)));
+        assert!(matches!(validate_launch_args("'{rom}"), Err(LaunchArgsError::UnterminatedQuote)));
+    }
+
+    #[test]
+    fn unknown_placeholder() {
+        let mut has_rom = false;
+        let result = replace_placeholders("{emulator}", &values(), &mut has_rom);
+        assert!(matches!(result, Err(LaunchArgsError::UnknownPlaceholder(name)) if name == "emulator"));
+        assert!(matches!(validate_launch_args("{rom"), Err(LaunchArgsError::UnterminatedPlaceholder)));
+    }
+
+    #[test]
+    fn literal_braces() {
+        let mut has_rom = false;
+        let result = replace_placeholders("{{rom}}", &values(), &mut has_rom).unwrap();
+        assert_eq!(result, "{rom}");
+        assert!(!has_rom);
+    }
+
+    #[test]
+    fn rom_placeholder_in_the_middle() {
+        let args = build_launch_args(r#"-L snes.so --rom="{rom}" --fullscreen"#, Path::new("/roms/A Game.sfc"), "SNES")
+            .unwrap();
+        assert_eq!(args, vec!["-L", "snes.so", "--rom=/roms/A Game.sfc", "--fullscreen"]);
+    }
+
+    #[test]
+    fn rom_is_first_without_placeholder() {
+        let args = build_launch_args("--fullscreen {platform}", Path::new("/roms/game.sfc"), "SNES").unwrap();
+        assert_eq!(args, vec!["/roms/game.sfc", "--fullscreen", "SNES"]);
+    }
+}
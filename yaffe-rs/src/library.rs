@@ -0,0 +1,266 @@
+use crate::data::{GameInfo, PlatformInfo, Transaction};
+use crate::logger::LogEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum LibraryError {
+    UnknownFormat,
+    UnknownPlatform(String),
+    /// A platform name that can't be used as a folder name
+    InvalidPlatformName(String),
+    /// A platform's id is already used by a different platform
+    PlatformConflict(String, i64, String),
+    File(std::io::Error),
+    Json(serde_json::Error),
+    Csv(csv::Error),
+    Xml(roxmltree::Error),
+    Query(crate::data::QueryError),
+}
+impl std::fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LibraryError::UnknownFormat => write!(f, "Library files must have a .json or .csv extension"),
+            LibraryError::UnknownPlatform(name) => write!(f, "No platform named {name}"),
+            LibraryError::InvalidPlatformName(name) => write!(f, "{name} can't be used as a platform name"),
+            LibraryError::PlatformConflict(name, id, existing) => {
+                write!(f, "Platform {name} can't be imported because its id {id} is already used by {existing}")
+            }
+            LibraryError::File(e) => write!(f, "Unable to access library file: {e}"),
+            LibraryError::Json(e) => write!(f, "Invalid library json: {e}"),
+            LibraryError::Csv(e) => write!(f, "Invalid library csv: {e}"),
+            LibraryError::Xml(e) => write!(f, "Invalid library xml: {e}"),
+            LibraryError::Query(e) => write!(f, "Unable to update database: {e}"),
+        }
+    }
+}
+impl std::error::Error for LibraryError {}
+
+impl From<std::io::Error> for LibraryError {
+    fn from(v: std::io::Error) -> Self { LibraryError::File(v) }
+}
+impl From<serde_json::Error> for LibraryError {
+    fn from(v: serde_json::Error) -> Self { LibraryError::Json(v) }
+}
+impl From<csv::Error> for LibraryError {
+    fn from(v: csv::Error) -> Self { LibraryError::Csv(v) }
+}
+impl From<roxmltree::Error> for LibraryError {
+    fn from(v: roxmltree::Error) -> Self { LibraryError::Xml(v) }
+}
+impl From<crate::data::QueryError> for LibraryError {
+    fn from(v: crate::data::QueryError) -> Self { LibraryError::Query(v) }
+}
+
+pub type LibraryResult<T> = Result<T, LibraryError>;
+
+/// A single game along with the platform it belongs to
+/// Platform values are repeated on every game so the same rows can be written as csv.
+/// Platforms without any games are stored as a row with an empty filename
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct LibraryEntry {
+    platform_id: i64,
+    platform: String,
+    platform_path: String,
+    platform_args: String,
+    platform_provider: String,
+    platform_extensions: String,
+    id: i64,
+    filename: String,
+    name: String,
+    overview: String,
+    players: i64,
+    rating: String,
+    released: String,
+    crc32: String,
+    md5: String,
+    sha1: String,
+    favorite: bool,
+    playtime: i64,
+    launchcount: i64,
+    lastrun: i64,
+    path: String,
+    args: String,
+    boxart: String,
+}
+
+enum LibraryFormat {
+    Json,
+    Csv,
+}
+impl LibraryFormat {
+    fn from_path(path: &Path) -> LibraryResult<LibraryFormat> {
+        match path.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+            Some("json") => Ok(LibraryFormat::Json),
+            Some("csv") => Ok(LibraryFormat::Csv),
+            _ => Err(LibraryError::UnknownFormat),
+        }
+    }
+}
+
+/// Writes every platform and game to a json or csv file, returning how many games were exported
+pub fn export_library(path: &Path) -> LibraryResult<usize> {
+    let format = LibraryFormat::from_path(path)?;
+
+    let mut entries = vec![];
+    for p in PlatformInfo::get_all()? {
+        let platform = LibraryEntry {
+            platform_id: p.id,
+            platform: p.platform.clone(),
+            platform_path: p.path.clone(),
+            platform_args: p.args.clone(),
+            platform_provider: p.provider.clone(),
+            platform_extensions: p.extensions.clone(),
+            ..Default::default()
+        };
+
+        let games = GameInfo::get_all(p.id)?;
+        if games.is_empty() {
+            entries.push(platform);
+            continue;
+        }
+
+        for g in games {
+            let boxart = crate::assets::get_asset_path(&p.platform, &g.name);
+            entries.push(LibraryEntry {
+                platform_id: p.id,
+                platform: p.platform.clone(),
+                platform_path: p.path.clone(),
+                platform_args: p.args.clone(),
+                platform_provider: p.provider.clone(),
+                platform_extensions: p.extensions.clone(),
+                id: g.id(),
+                filename: g.filename,
+                name: g.name,
+                overview: g.overview,
+                players: g.players,
+                rating: g.rating,
+                released: g.released,
+                crc32: g.crc32,
+                md5: g.md5,
+                sha1: g.sha1,
+                favorite: g.favorite,
+                playtime: g.playtime,
+                launchcount: g.launchcount,
+                lastrun: g.lastrun,
+                path: g.path,
+                args: g.args,
+                boxart: if boxart.exists() { boxart.to_string_lossy().to_string() } else { String::new() },
+            });
+        }
+    }
+
+    match format {
+        LibraryFormat::Json => std::fs::write(path, serde_json::to_string_pretty(&entries)?)?,
+        LibraryFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path)?;
+            for e in &entries {
+                writer.serialize(e)?;
+            }
+            writer.flush()?;
+        }
+    }
+
+    Ok(entries.iter().filter(|e| !e.filename.is_empty()).count())
+}
+
+/// Reads platforms and games from a json or csv file, merging them in to the database by platform and filename
+/// Returns how many games were imported
+pub fn import_library(path: &Path) -> LibraryResult<usize> {
+    let entries: Vec<LibraryEntry> = match LibraryFormat::from_path(path)? {
+        LibraryFormat::Json => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        LibraryFormat::Csv => csv::Reader::from_path(path)?.deserialize::<LibraryEntry>().collect::<Result<_, _>>()?,
+    };
+
+    // Platform ids can differ between libraries so they are matched by name
+    let mut platforms: HashMap<String, i64> =
+        PlatformInfo::get_all()?.into_iter().map(|p| (p.platform.to_lowercase(), p.id)).collect();
+
+    // Nothing is imported if any row fails
+    let transaction = Transaction::begin()?;
+    let mut count = 0;
+    let mut new_platforms = vec![];
+    let mut boxart_files = vec![];
+    for e in entries {
+        let platform = match platforms.get(&e.platform.to_lowercase()) {
+            Some(id) => *id,
+            None => {
+                // The id is also the scraper's platform id so it can't be changed to avoid a collision
+                if let Some((existing, _)) = platforms.iter().find(|(_, id)| **id == e.platform_id) {
+                    return Err(LibraryError::PlatformConflict(e.platform, e.platform_id, existing.clone()));
+                }
+                import_platform(e.platform_id, &e)?;
+                platforms.insert(e.platform.to_lowercase(), e.platform_id);
+                new_platforms.push(e.platform.clone());
+                e.platform_id
+            }
+        };
+
+        if e.filename.is_empty() {
+            continue;
+        }
+
+        let mut game = GameInfo::new(e.id, e.name, e.overview, e.players, e.rating, e.released, e.filename, platform);
+        game.crc32 = e.crc32;
+        game.md5 = e.md5;
+        game.sha1 = e.sha1;
+        game.favorite = e.favorite;
+        game.playtime = e.playtime;
+        game.launchcount = e.launchcount;
+        game.lastrun = e.lastrun;
+        game.path = e.path;
+        game.args = e.args;
+        GameInfo::merge(&game)?;
+
+        if !e.boxart.is_empty() {
+            boxart_files.push((e.boxart, crate::assets::get_asset_path(&e.platform, &game.name)));
+        }
+
+        count += 1;
+    }
+
+    transaction.commit()?;
+
+    // Folders are only created once everything has been saved so a failed import leaves nothing behind
+    for platform in new_platforms {
+        std::fs::create_dir_all(crate::paths::roms().join(&platform))?;
+        if let Some(assets) = crate::assets::get_asset_path(&platform, "").parent() {
+            std::fs::create_dir_all(assets)?;
+        }
+    }
+
+    // Boxart is copied in when the exported file is available, but existing boxart is kept
+    for (file, boxart) in boxart_files {
+        if !boxart.exists() && Path::new(&file).is_file() {
+            std::fs::copy(&file, boxart).log("Unable to copy boxart");
+        }
+    }
+    Ok(count)
+}
+
+/// Finds a platform by its name, ignoring case
+pub fn find_platform(name: &str) -> LibraryResult<PlatformInfo> {
+    PlatformInfo::get_all()?
+        .into_iter()
+        .find(|p| p.platform.eq_ignore_ascii_case(name))
+        .ok_or_else(|| LibraryError::UnknownPlatform(name.to_string()))
+}
+
+fn import_platform(id: i64, entry: &LibraryEntry) -> LibraryResult<()> {
+    crate::logger::info!("Importing new platform {}", entry.platform);
+
+    // Names come from the library file and become folder names, so they can't point outside the Roms folder
+    let name = &entry.platform;
+    if name.trim().is_empty() || matches!(name.as_str(), "." | "..") || crate::os::sanitize_file(name) != *name {
+        return Err(LibraryError::InvalidPlatformName(entry.platform.clone()));
+    }
+
+    let mut platform =
+        PlatformInfo::new(id, entry.platform.clone(), entry.platform_path.clone(), entry.platform_args.clone());
+    platform.provider = entry.platform_provider.clone();
+    platform.extensions = entry.platform_extensions.clone();
+    PlatformInfo::insert(&platform)?;
+    Ok(())
+}
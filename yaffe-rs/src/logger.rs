@@ -38,8 +38,9 @@ pub fn set_log_level(level: &str) {
 }
 
 static LOGGER: YaffeLogger = YaffeLogger;
-pub static FILE: LazyLock<Mutex<File>> =
-    LazyLock::new(|| Mutex::new(OpenOptions::new().write(true).create(true).truncate(true).open("./log.txt").unwrap()));
+pub static FILE: LazyLock<Mutex<File>> = LazyLock::new(|| {
+    Mutex::new(OpenOptions::new().write(true).create(true).truncate(true).open(crate::paths::log_file()).unwrap())
+});
 
 pub fn init() {
     log::set_logger(&LOGGER).unwrap();
@@ -18,19 +18,27 @@ const APP_LIST_ID: WidgetId = WidgetId::static_id(3);
 const SEARCH_BAR_ID: WidgetId = WidgetId::static_id(4);
 
 mod assets;
+mod cli;
 mod controls;
 mod data;
+mod gamelist;
 mod graphics;
 mod input;
 mod job_system;
+mod launch_args;
+mod library;
 mod logger;
 mod modals;
 mod os;
 mod overlay_state;
 mod overlay_window;
+mod paths;
 mod platform;
 mod plugins;
 mod pooled_cache;
+mod restrictions;
+mod rom_scan;
+mod rom_watcher;
 mod scraper;
 mod settings;
 mod state;
@@ -43,7 +51,7 @@ mod yaffe_window;
 use graphics::Graphics;
 use input::Actions;
 use job_system::Job;
-use logger::{error, PanicLogEntry};
+use logger::error;
 use overlay_state::OverlayState;
 use settings::SettingNames;
 use state::{Tile, TileGroup, YaffeState};
@@ -52,25 +60,36 @@ use utils::{append_app_ext, LogicalPosition, LogicalSize, PhysicalRect, Physical
 use widgets::*;
 use winit::window::{WindowAttributes, WindowLevel};
 
-const UPDATE_FILE_PATH: &str = "./yaffe-rs.update";
+const UPDATE_FILE_NAME: &str = "yaffe-rs.update";
 
 fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    paths::init(&mut args);
     logger::init();
     log_panics::init();
 
     //Check for and apply updates on startup
-    if std::path::Path::new(UPDATE_FILE_PATH).exists() {
-        let app = append_app_ext("./yaffe-rs");
-        match crate::utils::yaffe_helper("update", &[UPDATE_FILE_PATH, &app]) {
+    let update_file = paths::app_file(UPDATE_FILE_NAME);
+    if update_file.exists() {
+        let app = paths::app_file(&append_app_ext("yaffe-rs"));
+        match crate::utils::yaffe_helper("update", &[&update_file.to_string_lossy(), &app.to_string_lossy()]) {
             Ok(_) => return,
             Err(e) => error!("Updated file found, but unable to run updater {e:?}"),
         }
     }
-    crate::data::init_database().log_message_and_panic("Unable to create database");
+    if let Err(e) = crate::data::init_database() {
+        error!("Unable to open database: {e}");
+        return;
+    }
+
+    //Commands like importing a library run without showing any windows
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
 
     let (queue, notify) = job_system::start_job_system();
 
-    let settings = match settings::load_settings("./yaffe.settings", true) {
+    let settings = match settings::load_settings(paths::settings(), true) {
         Ok(settings) => settings,
         Err(e) => {
             logger::error!("Unable to load settings: {e:?}");
@@ -81,13 +100,38 @@ fn main() {
 
     let process = Rc::new(RefCell::new(None));
 
-    let yaffe_state = YaffeState::new(process.clone(), settings.clone(), queue.clone());
+    let rom_watcher = rom_watcher::RomWatcher::start(&paths::roms());
+    let yaffe_state = YaffeState::new(process.clone(), settings.clone(), queue.clone(), rom_watcher);
     let overlay_state = OverlayState::new(process.clone(), settings.clone());
 
     let overlay = ui::WidgetTree::<OverlayState>::new(build_overlay_tree(), overlay_state, OVERLAY_ID);
     let mut ui = ui::WidgetTree::<YaffeState>::new(build_main_tree(), yaffe_state, PLATFORM_LIST_ID);
 
-    plugins::load_plugins(&mut ui, "./plugins");
+    plugins::load_plugins(&mut ui, &paths::plugins());
+
+    //Restore the last active profile, including its restricted mode, then let shared cabinets choose who is playing
+    match data::ProfileInfo::get_all() {
+        Ok(profiles) => {
+            let last_active = data::ProfileInfo::get_last_active().unwrap_or_else(|e| {
+                error!("Unable to get last active profile: {e}");
+                data::DEFAULT_PROFILE
+            });
+            let profile = profiles.iter().find(|p| p.id == last_active);
+            if let Some(profile) = profile.or_else(|| profiles.iter().find(|p| p.id == data::DEFAULT_PROFILE)) {
+                if let Err(e) = ui.data.select_profile(profile.clone()) {
+                    error!("Unable to load profile settings: {e:?}");
+                }
+            }
+            //A restricted profile can't be left without its passcode so there is nothing to choose
+            let can_switch =
+                modals::verify_restricted_action(&ui.data, restrictions::RestrictedAction::Menu("Switch Profile"));
+            if profiles.len() > 1 && can_switch {
+                let content = modals::ProfilesModal::from(profiles);
+                modals::display_modal_raw(&mut ui, "Select Profile", None, content, modals::ModalSize::Third);
+            }
+        }
+        Err(e) => error!("Unable to get profiles: {e}"),
+    }
 
     let main = WindowAttributes::default().with_title("Yaffe").with_visible(true);
     let overlay_att = WindowAttributes::default()
@@ -306,3 +306,94 @@ pub(super) fn set_volume(volume: f32) -> VolumeResult<()> {
 }
 
 pub(super) fn sanitize_file(file: &str) -> String { file.replace("/", "").replace('\0', "") }
+
+/// Follows the XDG base directory spec, falling back to ~/.local/share
+pub(super) fn default_data_dir() -> Option<std::path::PathBuf> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(d) => std::path::PathBuf::from(d),
+        None => std::path::PathBuf::from(std::env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(data_home.join("yaffe"))
+}
+
+pub(super) fn watch_directory(folder: &std::path::Path, on_change: &dyn Fn()) -> super::PlatformResult<()> {
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+    if fd < 0 {
+        return Err(Error::last_os_error().into());
+    }
+
+    let mut watches = HashMap::new();
+    if let Err(e) = add_watches(fd, folder, &mut watches) {
+        unsafe { libc::close(fd) };
+        return Err(e.into());
+    }
+
+    const EVENT_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let len = unsafe { libc::read(fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len()) };
+        if len < 0 {
+            let error = Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(error.into());
+        }
+
+        let mut offset = 0;
+        while offset + EVENT_SIZE <= len as usize {
+            let event = unsafe { std::ptr::read_unaligned(buffer.as_ptr().add(offset) as *const libc::inotify_event) };
+            let name = &buffer[offset + EVENT_SIZE..offset + EVENT_SIZE + event.len as usize];
+            let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+
+            // Folders created after we started need to be watched as well
+            if event.mask & libc::IN_ISDIR != 0 && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                if let Some(parent) = watches.get(&event.wd) {
+                    let path = parent.join(OsStr::from_bytes(name));
+                    if let Err(e) = add_watches(fd, &path, &mut watches) {
+                        crate::logger::warn!("Unable to watch {} for changes: {e:?}", path.display());
+                    }
+                }
+            }
+            if event.mask & libc::IN_IGNORED != 0 {
+                watches.remove(&event.wd);
+            }
+
+            offset += EVENT_SIZE + event.len as usize;
+        }
+
+        if watches.is_empty() {
+            unsafe { libc::close(fd) };
+            return Err(super::PlatformError::Other(format!("{} was removed", folder.display())));
+        }
+        on_change();
+    }
+}
+
+fn add_watches(
+    fd: i32,
+    folder: &std::path::Path,
+    watches: &mut std::collections::HashMap<i32, std::path::PathBuf>,
+) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+    const WATCH_MASK: u32 =
+        libc::IN_CREATE | libc::IN_DELETE | libc::IN_MOVED_FROM | libc::IN_MOVED_TO | libc::IN_CLOSE_WRITE;
+
+    let path = std::ffi::CString::new(folder.as_os_str().as_bytes())?;
+    let wd = unsafe { libc::inotify_add_watch(fd, path.as_ptr(), WATCH_MASK) };
+    if wd < 0 {
+        return Err(Error::last_os_error());
+    }
+    watches.insert(wd, folder.to_path_buf());
+
+    for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        // Symlinked folders aren't followed so link cycles can't recurse forever
+        if entry.file_type()?.is_dir() {
+            add_watches(fd, &entry.path(), watches)?;
+        }
+    }
+    Ok(())
+}
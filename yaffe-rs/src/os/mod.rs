@@ -47,3 +47,12 @@ pub fn set_volume(delta: f32) -> PlatformResult<()> { os_impl::set_volume(delta)
 pub fn initialize_gamepad() -> Result<impl PlatformGamepad, i32> { Ok(os_impl::initialize_gamepad().unwrap()) }
 
 pub fn sanitize_file(file: &str) -> String { os_impl::sanitize_file(file) }
+
+/// The folder user data is stored in when one isn't given, if the platform has a standard location
+pub fn default_data_dir() -> Option<std::path::PathBuf> { os_impl::default_data_dir() }
+
+/// Blocks the current thread, calling on_change whenever a file in the folder or its subfolders changes
+/// Errors if the platform doesn't support being notified of changes
+pub fn watch_directory(folder: &std::path::Path, on_change: &dyn Fn()) -> PlatformResult<()> {
+    os_impl::watch_directory(folder, on_change)
+}
@@ -341,3 +341,11 @@ impl Drop for ComGuard {
 }
 
 pub fn sanitize_file(file: &str) -> String { file.replace(['\"', '*', '<', '>', '?', '\\', '/', ':'], "") }
+
+pub(super) fn default_data_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("APPDATA").map(|d| std::path::PathBuf::from(d).join("Yaffe"))
+}
+
+pub(super) fn watch_directory(_: &std::path::Path, _: &dyn Fn()) -> PlatformResult<()> {
+    Err(PlatformError::Other(String::from("Watching folders is not supported")))
+}
@@ -4,6 +4,7 @@ use crate::windowing::WindowHelper;
 use std::cell::RefCell;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
+use std::time::Instant;
 
 pub trait ExternalProcess {
     fn is_running(&mut self) -> bool;
@@ -24,14 +25,39 @@ impl ExternalProcess for std::process::Child {
     fn kill(&mut self) -> std::io::Result<()> { self.kill() }
 }
 
+/// A game that was launched, used to record how long it was played
+struct PlaySession {
+    platform: i64,
+    file: String,
+    start: Instant,
+}
+
 pub struct YaffeProcess {
     pub name: String,
     pub image: AssetKey,
     process: Box<dyn ExternalProcess>,
+    session: Option<PlaySession>,
 }
 impl YaffeProcess {
     pub fn new(name: &str, image: AssetKey, process: Box<dyn ExternalProcess>) -> YaffeProcess {
-        YaffeProcess { name: name.to_string(), image, process }
+        YaffeProcess { name: name.to_string(), image, process, session: None }
+    }
+
+    /// Records play time for a game once the process is gone
+    pub fn track_game(mut self, platform: i64, file: &str) -> YaffeProcess {
+        self.session = Some(PlaySession { platform, file: file.to_string(), start: Instant::now() });
+        self
+    }
+}
+impl Drop for YaffeProcess {
+    fn drop(&mut self) {
+        // Processes are dropped when they exit or are killed from the overlay, either way the session is over
+        if let Some(session) = &self.session {
+            let seconds = session.start.elapsed().as_secs() as i64;
+            crate::logger::info!("Session of {} ended after {seconds} seconds", self.name);
+            crate::data::GameInfo::add_play_time(session.platform, &session.file, seconds)
+                .log("Unable to update game play time");
+        }
     }
 }
 impl Deref for YaffeProcess {
@@ -0,0 +1,81 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const DATA_DIR_FLAG: &str = "--data-dir";
+const DATA_DIR_VARIABLE: &str = "YAFFE_DATA_DIR";
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static APP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Chooses where user data is stored, removing `--data-dir <folder>` from the arguments if it was given
+/// In order this uses the command line flag, the YAFFE_DATA_DIR environment variable,
+/// the application folder if it already has a database, then the platform's standard data folder.
+/// This needs to be called before anything is logged or loaded
+pub fn init(args: &mut Vec<String>) {
+    let mut folder = None;
+    if let Some(i) = args.iter().position(|a| a == DATA_DIR_FLAG) {
+        if i + 1 < args.len() {
+            folder = Some(PathBuf::from(args.remove(i + 1)));
+            args.remove(i);
+        }
+    } else if let Some(i) = args.iter().position(|a| a.starts_with(&format!("{DATA_DIR_FLAG}="))) {
+        folder = Some(PathBuf::from(&args.remove(i)[DATA_DIR_FLAG.len() + 1..]));
+    }
+
+    let folder = folder
+        .or_else(|| std::env::var_os(DATA_DIR_VARIABLE).filter(|v| !v.is_empty()).map(PathBuf::from))
+        .unwrap_or_else(default_data_dir);
+    let folder = std::path::absolute(&folder).unwrap_or(folder);
+
+    if let Err(e) = std::fs::create_dir_all(&folder) {
+        eprintln!("Unable to create data folder {}: {e}", folder.display());
+    }
+    let _ = DATA_DIR.set(folder);
+}
+
+fn default_data_dir() -> PathBuf {
+    // Installs from before the data folder was configurable keep everything next to the application
+    if app_dir().join("Yaffe.db").exists() {
+        return app_dir().to_path_buf();
+    }
+    crate::os::default_data_dir().unwrap_or_else(|| app_dir().to_path_buf())
+}
+
+/// Folder all user data is stored in
+pub fn data_dir() -> &'static Path { DATA_DIR.get_or_init(default_data_dir) }
+
+/// Folder the application and its read-only assets are installed to
+/// Falls back to the working directory when the assets aren't next to the executable, such as during development
+pub fn app_dir() -> &'static Path {
+    APP_DIR.get_or_init(|| {
+        let exe_dir = std::env::current_exe().ok().and_then(|e| e.parent().map(Path::to_path_buf));
+        match exe_dir {
+            Some(dir) if dir.join("Assets").is_dir() => dir,
+            _ => std::env::current_dir().unwrap_or_default(),
+        }
+    })
+}
+
+pub fn database() -> PathBuf { data_dir().join("Yaffe.db") }
+
+pub fn settings() -> PathBuf { data_dir().join("yaffe.settings") }
+
+/// Settings that a profile has changed from the shared settings file
+pub fn profile_settings(profile: i64) -> PathBuf { data_dir().join("profiles").join(format!("{profile}.settings")) }
+
+pub fn log_file() -> PathBuf { data_dir().join("log.txt") }
+
+pub fn plugins() -> PathBuf { data_dir().join("plugins") }
+
+pub fn roms() -> PathBuf { data_dir().join("Roms") }
+
+pub fn catalogues() -> PathBuf { data_dir().join("Catalogues") }
+
+/// Folder artwork for platforms and games is saved to
+pub fn artwork() -> PathBuf { data_dir().join("Assets") }
+
+/// An asset that is installed with the application, like fonts and icons
+pub fn app_asset(name: &str) -> PathBuf { app_dir().join("Assets").join(name) }
+
+/// A file that is installed with the application, like the helper or updates
+pub fn app_file(name: &str) -> PathBuf { app_dir().join(name) }
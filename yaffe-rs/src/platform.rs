@@ -1,20 +1,19 @@
-use crate::logger::PanicLogEntry;
-use crate::state::{GroupType, Tile, TileGroup};
+use crate::data::QueryResult;
+use crate::logger::{LogEntry, UserMessage};
+use crate::scraper::{GameScrapeResult, ScrapeMode, ServiceResponse, ServiceResult};
+use crate::state::{GroupType, MetadataSearch, Tile, TileGroup};
 use crate::{DeferredAction, YaffeState};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-pub fn get_database_info(state: &mut YaffeState) {
+pub fn get_database_info(state: &mut YaffeState, handler: &mut DeferredAction<YaffeState>) {
     crate::logger::info!("Refreshing information from database");
 
     let mut platforms = vec![];
     platforms.push(TileGroup::recents(String::from("Recent")));
-    for p in crate::data::PlatformInfo::get_all() {
-        platforms.push(TileGroup::emulator(p.id, p.platform));
-    }
-
-    for p in platforms.iter_mut() {
-        refresh_executable(state, p);
-    }
+    platforms.push(TileGroup::most_played(String::from("Most Played")));
+    platforms.push(TileGroup::favorites(String::from("Favorites")));
+    get_library_groups(state, &mut platforms).display_failure("Unable to load games", handler);
 
     for (i, p) in state.plugins.iter_mut().enumerate() {
         let name = String::from(p.name());
@@ -25,49 +24,299 @@ pub fn get_database_info(state: &mut YaffeState) {
     state.groups = platforms;
 }
 
+fn get_library_groups(state: &mut YaffeState, platforms: &mut Vec<TileGroup>) -> QueryResult<()> {
+    for c in crate::data::CollectionInfo::get_all()? {
+        platforms.push(TileGroup::collection(c));
+    }
+    for p in crate::data::PlatformInfo::get_all()? {
+        platforms.push(TileGroup::emulator(p.id, p.platform));
+    }
+
+    for p in platforms.iter_mut() {
+        refresh_executable(state, p)?;
+    }
+    Ok(())
+}
+
 pub fn scan_new_files(state: &mut YaffeState, handler: &mut DeferredAction<YaffeState>) {
+    let files = scan_rom_folders(state, handler);
+    for (platform, file) in &files {
+        start_game_search(state, *platform, file, ScrapeMode::New);
+    }
+
+    if !files.is_empty() {
+        handler.display_toast(&format!("Found {} new files, searching for information...", files.len()), 2.);
+    }
+}
+
+/// Picks up roms that were added, removed, or renamed after the Roms folder watcher saw a change
+pub fn sync_rom_files(state: &mut YaffeState, handler: &mut DeferredAction<YaffeState>) {
+    let files: Vec<_> = scan_rom_folders(state, handler)
+        .into_iter()
+        .map(|(platform, file)| {
+            let modified =
+                crate::scraper::get_rom_file(platform, &file).and_then(|p| p.metadata().ok()?.modified().ok());
+            (platform, file, modified)
+        })
+        .collect();
+    state.rom_watcher.retain_searched(&files);
+
     let mut count = 0;
+    for (platform, file, modified) in files {
+        // Files can change several times while being copied so only search for each once
+        if state.rom_watcher.mark_searched(platform, &file, modified) {
+            start_game_search(state, platform, &file, ScrapeMode::New);
+            count += 1;
+        }
+    }
+
+    if count != 0 {
+        handler.display_toast(&format!("Found {count} new files, searching for information..."), 2.);
+    }
+}
+
+/// Finds roms that aren't in the database yet and marks games whose rom no longer exists
+fn scan_rom_folders(state: &mut YaffeState, handler: &mut DeferredAction<YaffeState>) -> Vec<(i64, String)> {
+    // Checking every game can touch a lot of rows so save them all at once
+    let transaction = crate::data::Transaction::begin().display_failure("Unable to start scanning roms", handler);
+
+    let mut new_files = vec![];
+    let mut missing_changed = false;
+    let create_playlists = state.settings.get_i32(crate::SettingNames::CreateDiscPlaylists) != 0;
     for p in &state.groups {
         if let GroupType::Emulator = p.kind {
-            for entry in std::fs::read_dir(p.get_rom_path()).log_and_panic() {
-                let entry = entry.unwrap();
-                let path = entry.path();
-                if path.is_file() && is_allowed_file_type(&path) {
-                    let file = path.file_name().unwrap().to_string_lossy();
-                    crate::logger::info!("Found local game {file}");
-
-                    let exists = crate::data::GameInfo::exists(p.id, &file).log_and_panic();
-                    if !exists {
-                        let name = path.file_stem().unwrap().to_string_lossy();
-                        let name = clean_file_name(&name);
-                        let name = name.trim();
-
-                        crate::logger::info!("{name} not found in database, performing search");
-                        let job =
-                            crate::Job::SearchGame { exe: file.to_string(), name: name.to_string(), platform: p.id };
-                        state.queue.start_job(job);
-
-                        count += 1;
-                    }
+            let extensions = crate::data::PlatformInfo::get_extensions(p.id).log("Unable to get platform extensions");
+            let allowed = parse_extensions(&extensions);
+            let files = p.get_rom_path().and_then(|rom_path| {
+                crate::rom_scan::find_game_files(&rom_path, create_playlists, |f| is_allowed_file_type(f, &allowed))
+                    .map(|files| (rom_path, files))
+            });
+            let Some((rom_path, files)) =
+                files.display_failure(&format!("Unable to scan roms for {}", p.name), handler)
+            else {
+                continue;
+            };
+
+            let result = find_new_files(state, p, &rom_path, files, &mut new_files)
+                .and_then(|_| mark_missing_games(p, &rom_path, &mut missing_changed));
+            result.display_failure(&format!("Unable to update games for {}", p.name), handler);
+        }
+    }
+
+    if let Some(transaction) = transaction {
+        transaction.commit().display_failure("Unable to save scanned roms", handler);
+    }
+    if missing_changed {
+        state.refresh_list = true;
+    }
+    new_files
+}
+
+/// Adds the files of a platform that aren't in the database yet to `new_files`
+fn find_new_files(
+    state: &YaffeState,
+    platform: &TileGroup,
+    rom_path: &Path,
+    files: Vec<PathBuf>,
+    new_files: &mut Vec<(i64, String)>,
+) -> QueryResult<()> {
+    let unhashed: HashSet<String> = crate::data::GameInfo::get_unhashed(platform.id)?.into_iter().collect();
+    for path in files {
+        // Games in subfolders are stored by their path within the platform folder
+        let file = crate::rom_scan::get_relative_path(rom_path, &path);
+        crate::logger::info!("Found local game {file}");
+
+        if !crate::data::GameInfo::exists(platform.id, &file)? {
+            crate::logger::info!("{file} not found in database, performing search");
+            new_files.push((platform.id, file));
+        } else if unhashed.contains(&file) {
+            // Games added before hashing was supported still need their hashes
+            state.queue.start_job(crate::Job::HashRom { platform: platform.id, file });
+        }
+    }
+    Ok(())
+}
+
+/// Finds every game whose rom no longer exists
+pub fn find_missing_games(state: &mut YaffeState) -> QueryResult<Vec<crate::data::GameInfo>> {
+    let mut missing = vec![];
+    let mut missing_changed = false;
+    for p in &state.groups {
+        if let GroupType::Emulator = p.kind {
+            // Without the folder every game would look missing, so leave the platform as it is
+            let rom_path = match p.get_rom_path() {
+                Ok(rom_path) => rom_path,
+                Err(e) => {
+                    crate::logger::warn!("Unable to find roms for {}: {e}", p.name);
+                    continue;
+                }
+            };
+            missing.extend(mark_missing_games(p, &rom_path, &mut missing_changed)?);
+        }
+    }
+
+    if missing_changed {
+        state.refresh_list = true;
+    }
+    Ok(missing)
+}
+
+/// Updates whether each game of a platform has a rom, returning the ones that don't
+fn mark_missing_games(
+    platform: &TileGroup,
+    rom_path: &Path,
+    changed: &mut bool,
+) -> QueryResult<Vec<crate::data::GameInfo>> {
+    let mut result = vec![];
+    for g in crate::data::GameInfo::get_all(platform.id)? {
+        let missing = !rom_path.join(&g.filename).exists();
+        if missing != g.missing {
+            crate::logger::info!("Marking {} as missing: {missing}", g.filename);
+            crate::data::GameInfo::set_missing(platform.id, &g.filename, missing).log("Unable to mark game as missing");
+            *changed = true;
+        }
+
+        if missing {
+            result.push(g);
+        }
+    }
+    Ok(result)
+}
+
+/// Deletes boxart of a platform that doesn't belong to any of its games, returning how many were removed
+pub fn remove_orphaned_boxart(platform: i64) -> usize {
+    let Ok(name) = crate::data::PlatformInfo::get_name(platform) else {
+        return 0;
+    };
+    // Without the list of games every boxart would look unused
+    let Ok(games) = crate::data::GameInfo::get_all(platform) else {
+        return 0;
+    };
+
+    // Boxart is always stored as <game name>.jpg in the platform assets folder
+    let used: HashSet<PathBuf> = games.iter().map(|g| crate::assets::get_asset_path(&name, &g.name)).collect();
+    let folder = crate::assets::get_asset_path(&name, "");
+    let Some(Ok(entries)) = folder.parent().map(std::fs::read_dir) else {
+        return 0;
+    };
+
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let is_boxart = entry.path().extension().is_some_and(|e| e.eq_ignore_ascii_case("jpg"));
+        if is_boxart && !used.contains(&folder.with_file_name(entry.file_name())) {
+            crate::logger::info!("Removing orphaned boxart {}", entry.path().display());
+            match std::fs::remove_file(entry.path()) {
+                Ok(()) => count += 1,
+                Err(e) => crate::logger::warn!("Unable to remove orphaned boxart {e:?}"),
+            }
+        }
+    }
+    count
+}
+
+/// Searches for information on a game that is already in the database, replacing what is stored
+pub fn rescrape_game(state: &YaffeState, platform: i64, file: &str) {
+    crate::logger::info!("Searching again for information on {file}");
+    start_game_search(state, platform, file, ScrapeMode::Select);
+}
+
+/// Searches for information on every game of a platform, replacing what is stored
+pub fn rescrape_platform(state: &mut YaffeState, platform: i64, handler: &mut DeferredAction<YaffeState>) {
+    let games = crate::data::GameInfo::get_all(platform);
+    let Some(games) = games.display_failure("Unable to get platform games", handler) else {
+        return;
+    };
+    for g in &games {
+        start_game_search(state, platform, &g.filename, ScrapeMode::Refresh);
+    }
+    state.rescrape.remaining += games.len();
+    handler.display_toast(&format!("Searching for information on {} games...", games.len()), 2.);
+}
+
+/// Games from searching platforms again, collected so problems are shown once every search has finished
+#[derive(Default)]
+pub struct PlatformRescrape {
+    remaining: usize,
+    /// Games that had no exact match and used the first result
+    inexact: Vec<String>,
+    failed: usize,
+}
+
+/// Applies the result of searching a game again as part of a platform, returning a summary after the last one
+/// Platforms can have hundreds of games so the first result is used instead of asking about each one
+pub fn apply_rescrape(
+    state: &mut YaffeState,
+    result: ServiceResult<ServiceResponse<GameScrapeResult>>,
+) -> Option<String> {
+    match result {
+        Ok(result) => {
+            let exact = result.get_exact();
+            if exact.is_none() && !result.results.is_empty() {
+                state.rescrape.inexact.push(result.request.clone());
+            }
+            if let Some(game) = exact.or(result.results.first()) {
+                if let Err(e) = insert_game(state, &game.info, game.boxart.clone()) {
+                    crate::logger::warn!("Unable to save game {}: {e}", game.info.name);
+                    state.rescrape.failed += 1;
                 }
             }
         }
+        Err(e) => {
+            crate::logger::warn!("Unable to search game again: {e:?}");
+            state.rescrape.failed += 1;
+        }
     }
 
-    if count != 0 {
-        handler.display_toast(&format!("Found {count} new files, searching for information..."), 2.);
+    state.rescrape.remaining = state.rescrape.remaining.saturating_sub(1);
+    if state.rescrape.remaining != 0 {
+        return None;
+    }
+
+    let rescrape = std::mem::take(&mut state.rescrape);
+    let mut summary = vec![];
+    if !rescrape.inexact.is_empty() {
+        summary.push(format!(
+            "These games had no exact match so the first result was used, pick another with Search Game Again:\n{}",
+            rescrape.inexact.join("\n")
+        ));
     }
+    if rescrape.failed != 0 {
+        summary.push(format!("{} games could not be searched again", rescrape.failed));
+    }
+    (!summary.is_empty()).then(|| summary.join("\n\n"))
+}
+
+fn start_game_search(state: &YaffeState, platform: i64, file: &str, mode: ScrapeMode) {
+    let name = Path::new(file).file_stem().unwrap().to_string_lossy();
+    let name = clean_file_name(&name);
+
+    let providers = crate::scraper::get_provider_order(&state.settings, Some(platform));
+    let job =
+        crate::Job::SearchGame { exe: file.to_string(), name: name.trim().to_string(), platform, providers, mode };
+    state.queue.start_job(job);
 }
 
-fn refresh_executable(state: &mut YaffeState, platform: &mut TileGroup) {
+fn refresh_executable(state: &mut YaffeState, platform: &mut TileGroup) -> QueryResult<()> {
     match platform.kind {
         GroupType::Emulator => {
-            for g in crate::data::GameInfo::get_all(platform.id) {
+            let mut folders = std::collections::BTreeSet::new();
+            for g in crate::data::GameInfo::get_all(platform.id)? {
+                if let Some(folder) = crate::rom_scan::get_subfolder(&g.filename) {
+                    folders.insert(folder.to_string());
+                }
+
                 let boxart = crate::assets::get_asset_path(&platform.name, &g.name);
                 platform.tiles.push(Tile::new_game(&g, platform.id, boxart));
             }
 
             platform.tiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+            // Allow filtering by the subfolders roms are organised in to
+            // Search options are stored as a bit mask so only so many can be shown
+            if !folders.is_empty() {
+                let folders: Vec<&str> = folders.iter().map(|f| f.as_str()).take(usize::BITS as usize).collect();
+                platform.search.push(MetadataSearch::new("Folder", &folders));
+            }
         }
         GroupType::Plugin(_) => {
             //These are not stored from the database, but loaded at runtime
@@ -77,19 +326,48 @@ fn refresh_executable(state: &mut YaffeState, platform: &mut TileGroup) {
             crate::logger::info!("Getting recent games");
 
             let max = state.settings.get_f32(crate::SettingNames::RecentPageCount);
-            platform.tiles = crate::data::GameInfo::get_recent(max as i64);
+            platform.tiles = crate::data::GameInfo::get_recent(max as i64)?;
+        }
+        GroupType::MostPlayed => {
+            crate::logger::info!("Getting most played games");
+
+            let max = state.settings.get_i32(crate::SettingNames::MostPlayedCount);
+            platform.tiles = crate::data::GameInfo::get_most_played(max as i64)?;
+        }
+        GroupType::Favorites => {
+            crate::logger::info!("Getting favorite games");
+
+            platform.tiles = crate::data::GameInfo::get_favorites()?;
+        }
+        GroupType::Collection(id) => {
+            crate::logger::info!("Getting games in collection {}", platform.name);
+
+            platform.tiles = crate::data::CollectionInfo::get_games(id)?;
         }
     }
+    Ok(())
 }
 
-fn is_allowed_file_type(path: &std::path::Path) -> bool {
+fn is_allowed_file_type(path: &std::path::Path, allowed: &[String]) -> bool {
     if let Some(ext) = path.extension() {
-        let ext = ext.to_str().unwrap();
-        return !matches!(ext, "ini" | "srm");
+        let ext = ext.to_string_lossy().to_lowercase();
+        // Platforms without a list of extensions allow anything that isn't obviously not a rom
+        if allowed.is_empty() {
+            return !matches!(
+                ext.as_str(),
+                "ini" | "srm" | "sav" | "state" | "cfg" | "txt" | "nfo" | "pdf" | "png" | "jpg" | "jpeg" | "mcr"
+            );
+        }
+        return allowed.contains(&ext);
     }
     false
 }
 
+/// Parses a comma separated list of extensions, eg ".sfc, smc"
+fn parse_extensions(extensions: &str) -> Vec<String> {
+    extensions.split(',').map(|e| e.trim().trim_start_matches('.').to_lowercase()).filter(|e| !e.is_empty()).collect()
+}
+
 fn clean_file_name(file: &str) -> String {
     let mut i = 0;
     let mut index = 0;
@@ -136,39 +414,63 @@ fn clean_file_name(file: &str) -> String {
     cleaned_file
 }
 
-pub fn insert_platform(state: &mut YaffeState, data: &crate::data::PlatformInfo) {
+pub fn insert_platform(
+    state: &mut YaffeState,
+    data: &crate::data::PlatformInfo,
+) -> Result<(), Box<dyn std::error::Error>> {
     crate::logger::info!("Inserting new platform into database {}", data.platform);
 
     // Create Roms folder
-    let path = Path::new("./Roms");
-    if !path.exists() {
-        std::fs::create_dir(path).unwrap();
-    }
-    let path = path.join(&data.platform);
+    let path = crate::paths::roms().join(&data.platform);
     if !path.exists() {
-        std::fs::create_dir(path).unwrap();
+        std::fs::create_dir_all(path)?;
     }
 
     // Create Assets folder
-    let path = Path::new("./Assets").join(&data.platform);
+    let path = crate::paths::artwork().join(&data.platform);
     if !path.exists() {
-        std::fs::create_dir(path).unwrap();
+        std::fs::create_dir_all(path)?;
     }
 
-    crate::data::PlatformInfo::insert(data).log_and_panic();
+    crate::data::PlatformInfo::insert(data)?;
 
     state.refresh_list = true;
+    Ok(())
 }
 
-pub fn insert_game(state: &mut YaffeState, info: &crate::data::GameInfo, boxart: PathBuf) {
-    crate::logger::info!("Inserting new game into database {}", info.name);
+pub fn insert_game(state: &mut YaffeState, info: &crate::data::GameInfo, boxart: PathBuf) -> QueryResult<()> {
+    let plat_name = crate::data::PlatformInfo::get_name(info.platform())?;
 
-    crate::data::GameInfo::insert(info).log_and_panic();
+    // Searching again for a game replaces what was previously stored
+    match crate::data::GameInfo::get(info.platform(), &info.filename) {
+        Ok(existing) => {
+            crate::data::GameInfo::replace(info)?;
 
-    let plat_name = crate::data::PlatformInfo::get_name(info.platform()).unwrap();
+            let old_path = crate::assets::get_asset_path(&plat_name, &existing.name);
+            if old_path.exists() {
+                if boxart.as_os_str().is_empty() {
+                    // Nothing to replace it with so keep the old boxart under the new name
+                    let new_path = crate::assets::get_asset_path(&plat_name, &info.name);
+                    std::fs::rename(old_path, new_path).log("Unable to rename boxart");
+                } else {
+                    std::fs::remove_file(old_path).log("Unable to remove old boxart");
+                }
+            }
+        }
+        Err(_) => {
+            crate::logger::info!("Inserting new game into database {}", info.name);
+            crate::data::GameInfo::insert(info)?;
+        }
+    }
 
     let file_path = crate::assets::get_asset_path(&plat_name, &info.name);
-    state.queue.start_job(crate::Job::DownloadUrl { url: boxart, file_path });
+    if boxart.is_file() {
+        // Local providers can supply artwork that is already on disk
+        std::fs::copy(boxart, file_path).log("Unable to copy boxart");
+    } else if !boxart.as_os_str().is_empty() {
+        state.queue.start_job(crate::Job::DownloadUrl { url: boxart, file_path });
+    }
 
     state.refresh_list = true;
+    Ok(())
 }
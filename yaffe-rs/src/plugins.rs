@@ -5,7 +5,7 @@ use crate::YaffeState;
 use libloading::Library;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use yaffe_lib::{LoadItems, NavigationEntry, PluginFilter, TileQuery, YaffePlugin};
 
 pub struct Plugin {
@@ -53,8 +53,8 @@ fn load(ui: &mut WidgetTree<YaffeState>, path: &mut PathBuf) -> Result<(), Box<d
     Ok(())
 }
 
-pub fn load_plugins(ui: &mut WidgetTree<YaffeState>, directory: &str) {
-    if !std::path::Path::new(directory).exists() {
+pub fn load_plugins(ui: &mut WidgetTree<YaffeState>, directory: &Path) {
+    if !directory.exists() {
         std::fs::create_dir(directory).log_and_panic();
     }
     let path = std::fs::canonicalize(directory).unwrap();
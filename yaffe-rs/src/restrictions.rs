@@ -0,0 +1,183 @@
+use crate::settings::{SettingNames, SettingsFile};
+use crate::{Tile, YaffeState};
+
+/// Ratings from least to most mature, matched on the part of the rating before " - "
+const RATINGS: &[&str] = &["E", "E10+", "T", "M", "AO"];
+/// Ratings that are treated as more mature than any other rating
+const UNRATED: &[&str] = &["Not Rated", "Restricted"];
+/// Menu items that could be used to get around restricted mode so are always locked
+/// Settings hold the rules, editors can rename games and platforms out of them or change what is launched,
+/// searching again replaces the ratings they are checked against, and cleaning up deletes games and their history
+/// Collections and scanning for new roms are left alone since games are still checked when they are launched
+const ALWAYS_LOCKED_MENU_ITEMS: &[&str] = &[
+    "Settings",
+    "Switch Profile",
+    "Add Emulator",
+    "Edit Platform",
+    "Edit Game",
+    "Search Game Again",
+    "Search Platform Games Again",
+    "Clean Up Missing Roms",
+];
+/// Menu items that turn restricted mode off can never be locked
+const NEVER_LOCKED_MENU_ITEMS: &[&str] = &["Disable Restricted Mode"];
+
+/// Something that may not be allowed while restricted mode is on
+pub enum RestrictedAction<'a> {
+    /// Starting a game or plugin item
+    Launch(&'a Tile),
+    /// Choosing an item from the menu
+    Menu(&'a str),
+}
+
+/// Checks the restriction rules from settings to see if an action is locked while restricted mode is on
+pub fn is_restricted(state: &YaffeState, action: &RestrictedAction) -> bool {
+    match action {
+        RestrictedAction::Launch(tile) => is_tile_restricted(state, tile),
+        RestrictedAction::Menu(item) => is_menu_item_restricted(&state.settings, item),
+    }
+}
+
+fn is_tile_restricted(state: &YaffeState, tile: &Tile) -> bool {
+    // Plugins decide which of their items are restricted
+    let Some(platform) = tile.platform else {
+        return tile.restricted;
+    };
+
+    let platform = state.find_platform(platform).map(|group| group.name.as_str());
+    let rating = tile.metadata.get("Rating").map(String::as_str).unwrap_or_default();
+    is_game_restricted(&state.settings, &tile.name, &tile.file, platform, rating)
+}
+
+/// Games are checked against, in order, the deny and allow lists, locked platforms, then the maximum rating
+fn is_game_restricted(settings: &SettingsFile, name: &str, file: &str, platform: Option<&str>, rating: &str) -> bool {
+    let is_game = |g: &String| g.eq_ignore_ascii_case(name) || g.eq_ignore_ascii_case(file);
+    if split_setting(settings, SettingNames::RestrictedDenyGames, ';').iter().any(is_game) {
+        return true;
+    }
+    if split_setting(settings, SettingNames::RestrictedAllowGames, ';').iter().any(is_game) {
+        return false;
+    }
+
+    if let Some(platform) = platform {
+        if split_setting(settings, SettingNames::RestrictedPlatforms, ',')
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(platform))
+        {
+            return true;
+        }
+    }
+
+    // Unknown maximums only allow games rated for everyone rather than allowing everything
+    let max = rating_level(&settings.get_str(SettingNames::RestrictedMaxRating)).unwrap_or(0);
+    rating_level(rating).is_some_and(|level| level > max)
+}
+
+fn is_menu_item_restricted(settings: &SettingsFile, item: &str) -> bool {
+    if NEVER_LOCKED_MENU_ITEMS.contains(&item) {
+        return false;
+    }
+    ALWAYS_LOCKED_MENU_ITEMS.contains(&item)
+        || split_setting(settings, SettingNames::RestrictedMenuItems, ',').iter().any(|i| i.eq_ignore_ascii_case(item))
+}
+
+/// How mature a rating is, games without a rating (or that are pending one) return None
+fn rating_level(rating: &str) -> Option<usize> {
+    if UNRATED.iter().any(|r| r.eq_ignore_ascii_case(rating)) {
+        return Some(RATINGS.len());
+    }
+
+    let code = rating.split(" - ").next().unwrap_or_default().trim();
+    RATINGS.iter().position(|r| r.eq_ignore_ascii_case(code))
+}
+
+/// Game names often contain commas so lists of games are separated with semicolons instead
+fn split_setting(settings: &SettingsFile, setting: SettingNames, separator: char) -> Vec<String> {
+    let value = settings.get_str(setting);
+    value.split(separator).map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(values: &[(&str, &str)]) -> SettingsFile {
+        let mut settings = SettingsFile::default();
+        for (name, value) in values {
+            settings.set_setting(name, value).unwrap();
+        }
+        settings
+    }
+
+    #[test]
+    fn rating_levels() {
+        assert_eq!(rating_level("E"), Some(0));
+        assert_eq!(rating_level("e10+"), Some(1));
+        assert_eq!(rating_level("M - Mature 17+"), Some(3));
+        assert_eq!(rating_level("Not Rated"), Some(RATINGS.len()));
+        assert_eq!(rating_level("RESTRICTED"), Some(RATINGS.len()));
+        assert_eq!(rating_level("RP - Rating Pending"), None);
+        assert_eq!(rating_level(""), None);
+    }
+
+    #[test]
+    fn ratings_above_the_maximum_are_restricted() {
+        let settings = settings(&[]);
+        let restricted = |rating| is_game_restricted(&settings, "Game", "Game.sfc", None, rating);
+        assert!(!restricted("E - Everyone"));
+        assert!(!restricted("T - Teen"));
+        assert!(restricted("M - Mature 17+"));
+        assert!(restricted("Not Rated"));
+        // Games without a rating can't be judged so they are allowed
+        assert!(!restricted(""));
+    }
+
+    #[test]
+    fn unknown_maximum_only_allows_everyone() {
+        let settings = settings(&[("restricted_max_rating", "PG")]);
+        assert!(!is_game_restricted(&settings, "Game", "Game.sfc", None, "E"));
+        assert!(is_game_restricted(&settings, "Game", "Game.sfc", None, "E10+"));
+    }
+
+    #[test]
+    fn platforms_are_restricted() {
+        let settings = settings(&[("restricted_platforms", "Sega Genesis, nintendo 64")]);
+        assert!(is_game_restricted(&settings, "Game", "Game.z64", Some("Nintendo 64"), "E"));
+        assert!(!is_game_restricted(&settings, "Game", "Game.sfc", Some("Super Nintendo"), "E"));
+        assert!(!is_game_restricted(&settings, "Game", "Game.sfc", None, "E"));
+    }
+
+    #[test]
+    fn game_lists_come_first() {
+        let settings = settings(&[
+            ("restricted_platforms", "Nintendo 64"),
+            ("restricted_allow_games", "GoldenEye 007; Mario, Luigi.z64"),
+            ("restricted_deny_games", "Super Mario 64"),
+        ]);
+        let restricted = |name, file, rating| is_game_restricted(&settings, name, file, Some("Nintendo 64"), rating);
+        // The allow list overrides both the platform and the rating
+        assert!(!restricted("GoldenEye 007", "goldeneye.z64", "T"));
+        assert!(!restricted("Mario", "mario, luigi.z64", "E"));
+        // The deny list overrides everything else
+        assert!(restricted("super mario 64", "sm64.z64", "E"));
+        assert!(restricted("Other", "other.z64", "E"));
+    }
+
+    #[test]
+    fn deny_list_overrides_allow_list() {
+        let settings = settings(&[("restricted_allow_games", "Doom"), ("restricted_deny_games", "doom.wad")]);
+        assert!(is_game_restricted(&settings, "Doom", "doom.wad", None, "E"));
+    }
+
+    #[test]
+    fn menu_items() {
+        let settings = settings(&[("restricted_menu_items", "shut down, Exit Yaffe, Disable Restricted Mode")]);
+        assert!(is_menu_item_restricted(&settings, "Settings"));
+        assert!(is_menu_item_restricted(&settings, "Edit Game"));
+        assert!(is_menu_item_restricted(&settings, "Shut Down"));
+        assert!(is_menu_item_restricted(&settings, "Exit Yaffe"));
+        assert!(!is_menu_item_restricted(&settings, "Collections"));
+        // Restricted mode can always be turned off, even if it is listed
+        assert!(!is_menu_item_restricted(&settings, "Disable Restricted Mode"));
+    }
+}
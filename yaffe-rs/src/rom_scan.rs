@@ -0,0 +1,320 @@
+use crate::logger::LogEntry;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Finds the files in a platform rom folder and its subfolders that should each be shown as a single game
+/// Files referenced by .cue, .gdi or .m3u sheets are hidden, and discs of the same game are launched
+/// through an .m3u playlist if the platform allows them
+/// Missing playlists are only written in to the rom folder when `create_playlists` is set
+pub fn find_game_files(
+    folder: &Path,
+    create_playlists: bool,
+    is_allowed: impl Fn(&Path) -> bool,
+) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    collect_files(folder, &is_allowed, &mut files)?;
+
+    let mut hidden = HashSet::new();
+    for f in &files {
+        hidden.extend(get_referenced_files(f).iter().map(|r| path_key(r)));
+    }
+
+    // Group the discs of each game, keeping different folders and formats apart
+    let mut disc_sets: BTreeMap<(PathBuf, String, String), Vec<(u32, PathBuf)>> = BTreeMap::new();
+    for f in files.iter().filter(|f| !hidden.contains(&path_key(f))) {
+        let ext = f.extension().unwrap_or_default().to_string_lossy().to_ascii_lowercase();
+        if ext == "m3u" {
+            continue;
+        }
+
+        let stem = f.file_stem().unwrap_or_default().to_string_lossy();
+        if let Some((base, disc)) = split_disc_number(&stem) {
+            let parent = f.parent().unwrap_or(folder).to_path_buf();
+            disc_sets.entry((parent, base, ext)).or_default().push((disc, f.clone()));
+        }
+    }
+
+    for ((parent, base, _), mut discs) in disc_sets {
+        if discs.len() < 2 {
+            continue;
+        }
+
+        // Discs stay separate games if the platform can't launch playlists
+        let playlist = parent.join(format!("{base}.m3u"));
+        if !is_allowed(&playlist) {
+            continue;
+        }
+        if !playlist.exists() {
+            // Nothing is written to the rom folders unless it has been turned on
+            if !create_playlists {
+                continue;
+            }
+
+            crate::logger::info!("Creating playlist for {} discs of {base}", discs.len());
+
+            discs.sort_by_key(|(disc, _)| *disc);
+            let mut contents = String::new();
+            for (_, path) in &discs {
+                contents.push_str(&path.file_name().unwrap().to_string_lossy());
+                contents.push('\n');
+            }
+            std::fs::write(&playlist, contents)?;
+            files.push(playlist);
+        }
+        hidden.extend(discs.iter().map(|(_, path)| path_key(path)));
+    }
+
+    files.retain(|f| !hidden.contains(&path_key(f)));
+    Ok(files)
+}
+
+fn collect_files(folder: &Path, is_allowed: &impl Fn(&Path) -> bool, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(folder)? {
+        let entry = entry?;
+        let path = entry.path();
+        // The entry's own type doesn't follow symlinks, so linked folders that could loop back are skipped
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, is_allowed, files)?;
+        } else if path.is_file() && is_allowed(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Gets the path of a rom relative to its platform folder, which is what is stored in the database
+/// Always uses forward slashes so the library can be moved between operating systems
+pub fn get_relative_path(folder: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(folder).unwrap_or(path);
+    let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+    parts.join("/")
+}
+
+/// Gets the subfolder a rom is organised in to, if any
+pub fn get_subfolder(file: &str) -> Option<&str> { file.split_once('/').map(|(folder, _)| folder) }
+
+/// Gets the files a cue sheet, gdi, or playlist points to
+fn get_referenced_files(path: &Path) -> Vec<PathBuf> {
+    let ext = path.extension().unwrap_or_default().to_string_lossy().to_ascii_lowercase();
+    if !matches!(ext.as_str(), "cue" | "gdi" | "m3u") {
+        return vec![];
+    }
+
+    let folder = path.parent().unwrap_or(Path::new(""));
+    let contents = std::fs::read_to_string(path).log("Unable to read disc sheet");
+
+    let mut result = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        let file = match ext.as_str() {
+            // FILE "Track 01.bin" BINARY
+            "cue" => match line.get(..5) {
+                Some(command) if command.eq_ignore_ascii_case("FILE ") => first_token(&line[5..]),
+                _ => None,
+            },
+            // First line is the number of tracks, then <track> <lba> <type> <sector size> <file> <offset>
+            "gdi" if i > 0 => match line.find('"') {
+                Some(start) => first_token(&line[start..]),
+                None => line.split_whitespace().nth(4),
+            },
+            "m3u" if !line.is_empty() && !line.starts_with('#') => Some(line),
+            _ => None,
+        };
+
+        if let Some(file) = file {
+            result.push(folder.join(file));
+        }
+    }
+    result
+}
+
+/// Gets the first value of a line, which may be quoted
+fn first_token(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    match line.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => line.split_whitespace().next(),
+    }
+}
+
+/// Splits a name like "Game (USA) (Disc 2)" in to "Game (USA)" and 2
+fn split_disc_number(stem: &str) -> Option<(String, u32)> {
+    let lower = stem.to_ascii_lowercase();
+    for tag in ["(disc", "(disk", "(cd"] {
+        if let Some(start) = lower.find(tag) {
+            let end = start + lower[start..].find(')')? + 1;
+            // Handles both "Disc 2" and "Disc 2 of 3"
+            let number = lower[start + tag.len()..end - 1].split_whitespace().next()?;
+            let disc = number.parse::<u32>().ok()?;
+
+            let base = format!("{} {}", stem[..start].trim(), stem[end..].trim());
+            return Some((base.trim().to_string(), disc));
+        }
+    }
+    None
+}
+
+fn path_key(path: &Path) -> String { path.to_string_lossy().to_lowercase() }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A temporary rom folder that is removed once the test finishes
+    struct TestFolder(PathBuf);
+    impl TestFolder {
+        fn new(name: &str, files: &[(&str, &str)]) -> TestFolder {
+            let path = std::env::temp_dir().join(format!("yaffe-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            for (file, contents) in files {
+                let file = path.join(file);
+                std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+                std::fs::write(file, contents).unwrap();
+            }
+            TestFolder(path)
+        }
+
+        fn find(&self, create_playlists: bool, is_allowed: impl Fn(&Path) -> bool) -> Vec<String> {
+            let files = find_game_files(&self.0, create_playlists, is_allowed).unwrap();
+            let mut files: Vec<_> = files.iter().map(|f| get_relative_path(&self.0, f)).collect();
+            files.sort();
+            files
+        }
+    }
+    impl Drop for TestFolder {
+        fn drop(&mut self) { let _ = std::fs::remove_dir_all(&self.0); }
+    }
+
+    fn allow_all(_: &Path) -> bool { true }
+
+    fn allow_except_m3u(path: &Path) -> bool { !path.extension().is_some_and(|e| e == "m3u") }
+
+    #[test]
+    fn disc_numbers() {
+        assert_eq!(split_disc_number("Game (USA) (Disc 2)"), Some((String::from("Game (USA)"), 2)));
+        assert_eq!(split_disc_number("Game (Disc 1 of 3) (USA)"), Some((String::from("Game (USA)"), 1)));
+        assert_eq!(split_disc_number("Game (disk 3)"), Some((String::from("Game"), 3)));
+        assert_eq!(split_disc_number("Game (CD 2)"), Some((String::from("Game"), 2)));
+        assert_eq!(split_disc_number("Game (Disc A)"), None);
+        assert_eq!(split_disc_number("Game (USA)"), None);
+    }
+
+    #[test]
+    fn quoted_and_plain_tokens() {
+        assert_eq!(first_token(r#" "Track 01.bin" BINARY"#), Some("Track 01.bin"));
+        assert_eq!(first_token("track.bin BINARY"), Some("track.bin"));
+        assert_eq!(first_token("   "), None);
+    }
+
+    #[test]
+    fn sheet_references() {
+        let folder = TestFolder::new(
+            "sheet-references",
+            &[
+                ("game.cue", "FILE \"Track 01.bin\" BINARY\n  TRACK 01 MODE1/2352\nfile track2.bin BINARY\n"),
+                ("game.gdi", "2\n1 0 4 2352 \"track 01.raw\" 0\n2 600 0 2352 track02.bin 0\n"),
+                ("game.m3u", "#EXTM3U\nGame (Disc 1).chd\n\nGame (Disc 2).chd\n"),
+                ("game.bin", ""),
+            ],
+        );
+        let references = |file: &str| -> Vec<String> {
+            get_referenced_files(&folder.0.join(file)).iter().map(|f| get_relative_path(&folder.0, f)).collect()
+        };
+
+        assert_eq!(references("game.cue"), vec!["Track 01.bin", "track2.bin"]);
+        assert_eq!(references("game.gdi"), vec!["track 01.raw", "track02.bin"]);
+        assert_eq!(references("game.m3u"), vec!["Game (Disc 1).chd", "Game (Disc 2).chd"]);
+        assert!(references("game.bin").is_empty());
+    }
+
+    #[test]
+    fn cue_tracks_are_hidden() {
+        let folder = TestFolder::new(
+            "cue-tracks",
+            &[("Game.cue", "FILE \"Game (Track 1).bin\" BINARY\n"), ("Game (Track 1).bin", ""), ("Other.bin", "")],
+        );
+        assert_eq!(folder.find(false, allow_all), vec!["Game.cue", "Other.bin"]);
+    }
+
+    #[test]
+    fn discs_are_grouped_in_a_new_playlist() {
+        let folder = TestFolder::new("new-playlist", &[("Game (Disc 2).chd", ""), ("Game (Disc 1).chd", "")]);
+        assert_eq!(folder.find(true, allow_all), vec!["Game.m3u"]);
+
+        let playlist = std::fs::read_to_string(folder.0.join("Game.m3u")).unwrap();
+        assert_eq!(playlist, "Game (Disc 1).chd\nGame (Disc 2).chd\n");
+    }
+
+    #[test]
+    fn playlists_are_only_written_when_enabled() {
+        let folder = TestFolder::new("no-playlist", &[("Game (Disc 1).chd", ""), ("Game (Disc 2).chd", "")]);
+        assert_eq!(folder.find(false, allow_all), vec!["Game (Disc 1).chd", "Game (Disc 2).chd"]);
+        assert!(!folder.0.join("Game.m3u").exists());
+    }
+
+    #[test]
+    fn existing_playlists_are_used() {
+        let folder = TestFolder::new(
+            "existing-playlist",
+            &[
+                ("Game.m3u", "Game (Disc 1).chd\nGame (Disc 2).chd\n"),
+                ("Game (Disc 1).chd", ""),
+                ("Game (Disc 2).chd", ""),
+            ],
+        );
+        assert_eq!(folder.find(false, allow_all), vec!["Game.m3u"]);
+    }
+
+    #[test]
+    fn discs_stay_visible_when_playlists_are_not_allowed() {
+        let files = [
+            ("Game.m3u", "Game (Disc 1).chd\nGame (Disc 2).chd\n"),
+            ("Game (Disc 1).chd", ""),
+            ("Game (Disc 2).chd", ""),
+        ];
+        let folder = TestFolder::new("playlist-not-allowed", &files);
+        assert_eq!(folder.find(true, allow_except_m3u), vec!["Game (Disc 1).chd", "Game (Disc 2).chd"]);
+
+        let folder = TestFolder::new("new-playlist-not-allowed", &files[1..]);
+        assert_eq!(folder.find(true, allow_except_m3u), vec!["Game (Disc 1).chd", "Game (Disc 2).chd"]);
+        assert!(!folder.0.join("Game.m3u").exists());
+    }
+
+    #[test]
+    fn different_formats_are_not_grouped() {
+        let folder = TestFolder::new("mixed-formats", &[("Game (Disc 1).chd", ""), ("Game (Disc 2).iso", "")]);
+        assert_eq!(folder.find(true, allow_all), vec!["Game (Disc 1).chd", "Game (Disc 2).iso"]);
+    }
+
+    #[test]
+    fn subfolders_are_searched() {
+        let folder = TestFolder::new(
+            "subfolders",
+            &[("Top.sfc", ""), ("Hacks/Hack.sfc", ""), ("Hacks/Translations/Game.sfc", ""), ("notes.txt", "")],
+        );
+        let is_rom = |f: &Path| f.extension().is_some_and(|e| e == "sfc");
+        assert_eq!(folder.find(false, is_rom), vec!["Hacks/Hack.sfc", "Hacks/Translations/Game.sfc", "Top.sfc"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_folders_are_skipped() {
+        let folder = TestFolder::new("symlinks", &[("Hacks/Hack.sfc", "")]);
+        std::os::unix::fs::symlink(&folder.0, folder.0.join("Hacks/Loop")).unwrap();
+        assert_eq!(folder.find(false, allow_all), vec!["Hacks/Hack.sfc"]);
+    }
+
+    #[test]
+    fn relative_paths_use_forward_slashes() {
+        let folder = Path::new("roms").join("SNES");
+        assert_eq!(get_relative_path(&folder, &folder.join("Hacks").join("Hack.sfc")), "Hacks/Hack.sfc");
+        assert_eq!(get_relative_path(&folder, &folder.join("Game.sfc")), "Game.sfc");
+    }
+
+    #[test]
+    fn subfolder_of_file() {
+        assert_eq!(get_subfolder("Hacks/Hack.sfc"), Some("Hacks"));
+        assert_eq!(get_subfolder("Hacks/Translations/Game.sfc"), Some("Hacks"));
+        assert_eq!(get_subfolder("Game.sfc"), None);
+    }
+}
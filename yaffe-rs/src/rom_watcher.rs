@@ -0,0 +1,102 @@
+use crate::logger::{LogEntry, PanicLogEntry};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How long the Roms folder has to stay unchanged before it is scanned, so files being copied are only picked up once
+const SETTLE_TIME: Duration = Duration::from_secs(2);
+/// How often the Roms folder is checked when it can't be watched for changes
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Watches the Roms folder in the background for files being added, removed, or renamed
+pub struct RomWatcher {
+    last_change: Arc<Mutex<Option<Instant>>>,
+    searched: HashSet<(i64, String, Option<SystemTime>)>,
+}
+impl RomWatcher {
+    pub fn start(folder: &Path) -> RomWatcher {
+        if !folder.exists() {
+            std::fs::create_dir_all(folder).log("Unable to create Roms folder");
+        }
+
+        let last_change = Arc::new(Mutex::new(None));
+        let changed = last_change.clone();
+        let folder = folder.to_path_buf();
+        std::thread::spawn(move || {
+            let on_change = || *changed.lock().log_and_panic() = Some(Instant::now());
+            if let Err(e) = crate::os::watch_directory(&folder, &on_change) {
+                crate::logger::warn!(
+                    "Unable to watch {} for changes, checking periodically instead: {e:?}",
+                    folder.display()
+                );
+                poll_directory(&folder, &on_change);
+            }
+        });
+
+        RomWatcher { last_change, searched: HashSet::new() }
+    }
+
+    /// A watcher that never sees any changes, for when Yaffe is run without the UI
+    pub fn disabled() -> RomWatcher { RomWatcher { last_change: Arc::new(Mutex::new(None)), searched: HashSet::new() } }
+
+    /// Returns true once if the Roms folder has changed and then settled since this was last called
+    pub fn take_changes(&self) -> bool {
+        let mut last_change = self.last_change.lock().log_and_panic();
+        match *last_change {
+            Some(time) if time.elapsed() >= SETTLE_TIME => {
+                *last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Records that a search was started for a file, returning false if one already was for this version of it
+    /// Searches that failed or weren't saved are tried again once the file is modified
+    pub fn mark_searched(&mut self, platform: i64, file: &str, modified: Option<SystemTime>) -> bool {
+        self.searched.insert((platform, file.to_string(), modified))
+    }
+
+    /// Forgets searches for files that are no longer new, either because they were added or removed
+    pub fn retain_searched(&mut self, new_files: &[(i64, String, Option<SystemTime>)]) {
+        self.searched.retain(|f| new_files.contains(f));
+    }
+}
+
+/// Compares the files in a folder at an interval for platforms that can't be notified of changes
+fn poll_directory(folder: &Path, on_change: &dyn Fn()) {
+    let mut previous = get_snapshot(folder);
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let current = get_snapshot(folder);
+        if current != previous {
+            on_change();
+            previous = current;
+        }
+    }
+}
+
+fn get_snapshot(folder: &Path) -> Vec<(PathBuf, u64, Option<SystemTime>)> {
+    let mut snapshot = vec![];
+    add_to_snapshot(folder, &mut snapshot);
+    snapshot.sort();
+    snapshot
+}
+
+fn add_to_snapshot(folder: &Path, snapshot: &mut Vec<(PathBuf, u64, Option<SystemTime>)>) {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Symlinked folders aren't followed so link cycles can't recurse forever
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            add_to_snapshot(&path, snapshot);
+        } else if let Ok(metadata) = entry.metadata() {
+            snapshot.push((path, metadata.len(), metadata.modified().ok()));
+        }
+    }
+}
@@ -43,8 +43,16 @@ stringy_enum! {
         FontColor("font_color") = SettingValue::Tuple((0.95, 0.95, 0.95, 1.)),
         AccentColor("accent_color") = SettingValue::Tuple((0.25, 0.3, 1., 1.)),
         RecentPageCount("recent_page_count") = SettingValue::F32(1.),
+        MostPlayedCount("most_played_count") = SettingValue::I32(10),
+        CreateDiscPlaylists("create_disc_playlists") = SettingValue::I32(0),
         AssetCacheSizeMb("asset_cache_size_mb") = SettingValue::I32(64),
         LoggingLevel("logging_level") = SettingValue::String(String::from("Info")),
+        MetadataProviders("metadata_providers") = SettingValue::String(String::from("TheGamesDB")),
+        RestrictedMaxRating("restricted_max_rating") = SettingValue::String(String::from("T")),
+        RestrictedPlatforms("restricted_platforms") = SettingValue::String(String::new()),
+        RestrictedAllowGames("restricted_allow_games") = SettingValue::String(String::new()),
+        RestrictedDenyGames("restricted_deny_games") = SettingValue::String(String::new()),
+        RestrictedMenuItems("restricted_menu_items") = SettingValue::String(String::new()),
     }
 }
 
@@ -54,13 +62,7 @@ macro_rules! settings_get {
         pub fn $name(&self, setting: crate::settings::SettingNames) -> $ty {
             let key = crate::settings::SettingNames::to_string(setting);
 
-            let value = if let Some(value) = self.settings.get(key) {
-                value.clone()
-            } else {
-                SettingNames::get_default(key).unwrap()
-            };
-
-            if let $setting(value) = value {
+            if let $setting(value) = self.get_value(key) {
                 return value.clone();
             }
             panic!("Accessed setting using incorrect type");
@@ -73,6 +75,8 @@ pub struct SettingsFile {
     settings: HashMap<String, SettingValue>,
     path: std::path::PathBuf,
     last_write: SystemTime,
+    /// Overrides of the current profile, these take priority over the shared settings
+    profile: Option<Box<SettingsFile>>,
 }
 impl SettingsFile {
     pub fn default() -> SettingsFile {
@@ -80,43 +84,69 @@ impl SettingsFile {
             settings: HashMap::default(),
             path: std::path::PathBuf::default(),
             last_write: SystemTime::now(),
+            profile: None,
         }
     }
 
     /// Returns all possible settings that can be set and their current (or default) values
     pub fn get_full_settings(&self) -> Vec<(String, SettingValue)> {
-        let mut result = vec![];
-        for name in SETTINGS {
-            //Get configured value if it exists, otherwise default
-            let value = if let Some(value) = self.settings.get(*name) {
-                value.clone()
-            } else {
-                SettingNames::get_default(name).unwrap()
-            };
-
-            result.push((name.to_string(), value));
+        SETTINGS.iter().map(|name| (name.to_string(), self.get_value(name))).collect()
+    }
+
+    /// Gets the profile override if there is one, then the configured value, otherwise the default
+    fn get_value(&self, name: &str) -> SettingValue {
+        if let Some(value) = self.profile.as_ref().and_then(|p| p.settings.get(name)) {
+            return value.clone();
+        }
+        self.get_shared_value(name)
+    }
+
+    fn get_shared_value(&self, name: &str) -> SettingValue {
+        match self.settings.get(name) {
+            Some(value) => value.clone(),
+            None => SettingNames::get_default(name).unwrap(),
         }
-        result
     }
 
+    /// Changes a setting, which only changes the current profile if it has overrides
     pub fn set_setting(&mut self, name: &str, value: &str) -> Result<(), SettingLoadError> {
         assert!(SETTINGS.contains(&name));
 
-        let setting = SettingNames::get_default(name).unwrap();
+        //Profiles only need to store values that differ from the shared settings
+        let setting = match self.profile {
+            Some(_) => self.get_shared_value(name),
+            None => SettingNames::get_default(name).unwrap(),
+        };
+        let settings = match self.profile.as_mut() {
+            Some(profile) => &mut profile.settings,
+            None => &mut self.settings,
+        };
+
         let value = setting_from_string(name, &setting, value, true)?;
         match value {
             //Add or insert new value
             Some(v) => {
-                self.settings.entry(name.to_string()).and_modify(|e| *e = v.clone()).or_insert(v);
+                settings.entry(name.to_string()).and_modify(|e| *e = v.clone()).or_insert(v);
             }
             //Value was either removed or the default, don't add it
             None => {
-                self.settings.remove(name);
+                settings.remove(name);
             }
         }
         Ok(())
     }
 
+    /// Uses the overrides stored at the path, which doesn't need to exist yet
+    /// Passing None uses only the shared settings
+    pub fn set_profile(&mut self, path: Option<std::path::PathBuf>) -> SettingsResult<()> {
+        self.profile = match path {
+            Some(path) if path.exists() => Some(Box::new(load_settings(path, true)?)),
+            Some(path) => Some(Box::new(SettingsFile { path, ..SettingsFile::default() })),
+            None => None,
+        };
+        Ok(())
+    }
+
     settings_get!(get_f32, f32, SettingValue::F32);
     settings_get!(get_i32, i32, SettingValue::I32);
     settings_get!(get_str, String, SettingValue::String);
@@ -128,8 +158,16 @@ impl SettingsFile {
             file.write_all(line.as_bytes())
         }
 
+        //Only the profile's overrides are changed while a profile is active
+        if let Some(profile) = &self.profile {
+            if let Some(folder) = profile.path.parent() {
+                std::fs::create_dir_all(folder)?;
+            }
+            return profile.serialize();
+        }
+
         //write base settings
-        let mut file = std::fs::OpenOptions::new().write(true).truncate(true).open(self.path.clone())?;
+        let mut file = std::fs::OpenOptions::new().write(true).create(true).truncate(true).open(self.path.clone())?;
         for (key, value) in self.settings.iter() {
             write_line(key, value, &mut file)?;
         }
@@ -171,6 +209,7 @@ pub fn load_settings<P: Clone + AsRef<Path>>(path: P, validate_names: bool) -> S
         settings: load_settings_from_path(path, validate_names)?,
         path: path_buf,
         last_write: last_write.unwrap(),
+        profile: None,
     };
 
     Ok(settings)
@@ -178,6 +217,11 @@ pub fn load_settings<P: Clone + AsRef<Path>>(path: P, validate_names: bool) -> S
 
 /// Checks for and loads any updates to the settings file
 pub fn update_settings(settings: &mut SettingsFile) -> SettingsResult<bool> {
+    let mut updated = false;
+    if let Some(profile) = settings.profile.as_mut() {
+        updated = update_settings(profile)?;
+    }
+
     //We log an error if the file isnt found in load_settings
     //Since this is already logged we dont need to get logging it every frame
     if settings.path.as_path().exists() {
@@ -190,7 +234,7 @@ pub fn update_settings(settings: &mut SettingsFile) -> SettingsResult<bool> {
         }
     }
 
-    Ok(false)
+    Ok(updated)
 }
 
 /// Loads settings from a file path
@@ -1,17 +1,19 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::iter::Step;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::rc::Rc;
 
 use crate::assets::AssetKey;
-use crate::data::GameInfo;
+use crate::data::{CollectionInfo, GameInfo, ProfileInfo, DEFAULT_PROFILE};
 use crate::job_system::ThreadSafeJobQueue;
-use crate::logger::{LogEntry, PanicLogEntry};
+use crate::logger::LogEntry;
 use crate::modals::RestrictedMode;
 use crate::overlay_state::{ExternalProcess, YaffeProcess};
+use crate::platform::PlatformRescrape;
 use crate::plugins::Plugin;
-use crate::settings::SettingsFile;
+use crate::rom_watcher::RomWatcher;
+use crate::settings::{SettingsFile, SettingsResult};
 use yaffe_lib::{NavigationEntry, PluginFilter, PluginTile, SelectedAction, TileType};
 
 #[derive(PartialEq, Copy, Clone, Debug)]
@@ -19,11 +21,26 @@ pub enum GroupType {
     Emulator,
     Plugin(usize),
     Recents,
+    MostPlayed,
+    Favorites,
+    Collection(i64),
 }
 impl GroupType {
     pub fn allow_edit(&self) -> bool { matches!(self, GroupType::Emulator) }
 
-    pub fn show_count(&self) -> bool { matches!(self, GroupType::Emulator | GroupType::Recents) }
+    pub fn show_count(&self) -> bool { self.is_game_list() }
+
+    /// Whether the tiles of this group are games stored in the database
+    pub fn is_game_list(&self) -> bool {
+        matches!(
+            self,
+            GroupType::Emulator
+                | GroupType::Recents
+                | GroupType::MostPlayed
+                | GroupType::Favorites
+                | GroupType::Collection(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -174,6 +191,25 @@ impl TileGroup {
         super::TileGroup { id: -1, name, tiles: vec![], kind: GroupType::Recents, search: vec![] }
     }
 
+    pub fn most_played(name: String) -> TileGroup {
+        super::TileGroup { id: -2, name, tiles: vec![], kind: GroupType::MostPlayed, search: vec![] }
+    }
+
+    pub fn favorites(name: String) -> TileGroup {
+        super::TileGroup { id: -3, name, tiles: vec![], kind: GroupType::Favorites, search: vec![] }
+    }
+
+    pub fn collection(collection: CollectionInfo) -> TileGroup {
+        // Collections are numbered after the other built in groups so they don't clash with platforms
+        super::TileGroup {
+            id: -3 - collection.id,
+            name: collection.name,
+            tiles: vec![],
+            kind: GroupType::Collection(collection.id),
+            search: vec![],
+        }
+    }
+
     pub fn plugin(plugin_index: usize, name: String, filters: &[PluginFilter]) -> TileGroup {
         super::TileGroup {
             id: plugin_index as i64,
@@ -184,7 +220,9 @@ impl TileGroup {
         }
     }
 
-    pub fn get_rom_path(&self) -> PathBuf { std::fs::canonicalize(Path::new("Roms").join(&self.name)).unwrap() }
+    pub fn get_rom_path(&self) -> std::io::Result<PathBuf> {
+        std::fs::canonicalize(crate::paths::roms().join(&self.name))
+    }
 }
 
 pub struct Tile {
@@ -192,7 +230,12 @@ pub struct Tile {
     pub tile_type: TileType,
     pub name: String,
     pub description: String,
+    /// Plugins can mark their items as restricted, games are checked against the restriction rules instead
     pub restricted: bool,
+    /// Platform of games from the database, plugin items don't have one
+    pub platform: Option<i64>,
+    pub favorite: bool,
+    pub missing: bool,
     // We need to store the group on here because recents can be from multiple platforms
     pub group_id: i64,
     pub boxart: AssetKey,
@@ -209,6 +252,9 @@ impl Tile {
             group_id,
             boxart: item.thumbnail.into(),
             restricted: item.restricted,
+            platform: None,
+            favorite: false,
+            missing: false,
         }
     }
 
@@ -217,9 +263,12 @@ impl Tile {
         metadata.insert(String::from("Players"), info.players.to_string());
         metadata.insert(String::from("Rating"), info.rating.clone());
         metadata.insert(String::from("Released"), info.released.clone());
+        metadata.insert(String::from("Play Time"), format_play_time(info.playtime));
+        metadata.insert(String::from("Times Played"), info.launchcount.to_string());
+        if let Some(folder) = crate::rom_scan::get_subfolder(&info.filename) {
+            metadata.insert(String::from("Folder"), folder.to_string());
+        }
 
-        let restricted =
-            matches!(info.rating.as_str(), "M - Mature 17+" | "Restricted" | "Not Rated" | "AO - Adult Only 18+");
         Self {
             file: info.filename.clone(),
             name: info.name.clone(),
@@ -228,7 +277,10 @@ impl Tile {
             group_id,
             boxart: AssetKey::File(boxart),
             metadata,
-            restricted,
+            restricted: false,
+            platform: Some(group_id),
+            favorite: info.favorite,
+            missing: info.missing,
         }
     }
 
@@ -262,24 +314,52 @@ impl Tile {
                     SelectedAction::Process(mut p) => Box::new(p.spawn()?) as Box<dyn ExternalProcess>,
                 }
             }
-            GroupType::Emulator | GroupType::Recents => {
+            GroupType::Emulator
+            | GroupType::Recents
+            | GroupType::MostPlayed
+            | GroupType::Favorites
+            | GroupType::Collection(_) => {
+                if self.missing {
+                    return Err(format!("Rom for {} could not be found", self.name).into());
+                }
+
                 let id = group.id;
-                //This should never fail since we got it from the database
-                let (path, args) = crate::data::PlatformInfo::get_info(id).log_message_and_panic("Platform not found");
+                let (mut path, mut args) = crate::data::PlatformInfo::get_info(id)?;
+
+                // Games can override how their platform is launched
+                let game = GameInfo::get(id, &self.file).log("Unable to get game launch options");
+                if !game.path.is_empty() {
+                    path = game.path;
+                }
+                if !game.args.is_empty() {
+                    args = game.args;
+                }
                 crate::data::GameInfo::update_last_run(id, &self.file).log("Unable to update game last run");
 
-                let path = std::fs::canonicalize(path)?;
-                let mut process = &mut std::process::Command::new(path);
-                let exe_path = group.get_rom_path().join(&self.file);
+                // Relative emulator paths are relative to the install, not wherever Yaffe was started from
+                let path = std::fs::canonicalize(crate::paths::app_dir().join(path))?;
+                let exe_path = group.get_rom_path()?.join(&self.file);
+                let args = crate::launch_args::build_launch_args(&args, &exe_path, &group.name)?;
 
-                process = process.arg(exe_path.to_str().unwrap());
-                if !args.is_empty() {
-                    process = process.args(args.split(' '));
-                }
-                Box::new(process.spawn()?) as Box<dyn ExternalProcess>
+                let process = std::process::Command::new(path).args(args).spawn()?;
+                Box::new(process) as Box<dyn ExternalProcess>
             }
         };
-        Ok(Some(YaffeProcess::new(&self.name, self.boxart.clone(), child)))
+        let process = YaffeProcess::new(&self.name, self.boxart.clone(), child);
+        if group.kind.is_game_list() {
+            Ok(Some(process.track_game(group.id, &self.file)))
+        } else {
+            Ok(Some(process))
+        }
+    }
+}
+
+fn format_play_time(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
     }
 }
 
@@ -315,8 +395,11 @@ pub struct YaffeState {
     pub queue: ThreadSafeJobQueue,
     pub filter: Option<MetadataSearch>,
     pub restricted_mode: RestrictedMode,
+    pub profile: ProfileInfo,
     pub refresh_list: bool,
     pub settings: SettingsFile,
+    pub rom_watcher: RomWatcher,
+    pub rescrape: PlatformRescrape,
     pub running: bool,
     pub navigation_stack: RefCell<Vec<NavigationEntry>>,
 }
@@ -325,6 +408,7 @@ impl YaffeState {
         process: Rc<RefCell<Option<YaffeProcess>>>,
         settings: SettingsFile,
         queue: ThreadSafeJobQueue,
+        rom_watcher: RomWatcher,
     ) -> YaffeState {
         YaffeState {
             process,
@@ -333,9 +417,12 @@ impl YaffeState {
             plugins: vec![],
             filter: None,
             restricted_mode: RestrictedMode::Off,
+            profile: ProfileInfo { id: DEFAULT_PROFILE, name: String::from("Default"), ..Default::default() },
             queue,
             refresh_list: true,
             settings,
+            rom_watcher,
+            rescrape: PlatformRescrape::default(),
             running: true,
             navigation_stack: RefCell::new(Vec::new()),
         }
@@ -353,8 +440,29 @@ impl YaffeState {
 
     pub fn find_group(&self, id: i64) -> Option<&TileGroup> { self.groups.iter().find(|p| p.id == id) }
 
+    /// Finds the group of a platform, ignoring plugins which can share ids with platforms
+    pub fn find_platform(&self, id: i64) -> Option<&TileGroup> {
+        self.groups.iter().find(|p| matches!(p.kind, GroupType::Emulator) && p.id == id)
+    }
+
     pub fn exit(&mut self) { self.running = false; }
 
+    /// Switches to a profile, using its history, restricted mode, and setting overrides
+    pub fn select_profile(&mut self, profile: ProfileInfo) -> SettingsResult<()> {
+        //The default profile uses the shared settings directly
+        let overrides = (profile.id != DEFAULT_PROFILE).then(|| crate::paths::profile_settings(profile.id));
+        //Stay on the current profile if its overrides can't be loaded so settings and history always match
+        self.settings.set_profile(overrides)?;
+
+        ProfileInfo::set_current(profile.id);
+        ProfileInfo::set_last_active(profile.id).log("Unable to save active profile");
+        self.restricted_mode = RestrictedMode::from_profile(&profile);
+        self.profile = profile;
+        self.selected = SelectedItem::new();
+        self.refresh_list = true;
+        Ok(())
+    }
+
     pub fn is_overlay_active(&self) -> bool { self.process.borrow().is_some() }
 
     pub fn set_process(&self, process: YaffeProcess) { *self.process.borrow_mut() = Some(process) }
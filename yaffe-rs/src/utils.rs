@@ -124,8 +124,13 @@ impl ScaleFactor for f32 {
 }
 
 pub fn yaffe_helper(action: &str, args: &[&str]) -> std::io::Result<std::process::Child> {
-    let helper_path = append_app_ext("./yaffe-helper");
-    std::process::Command::new(helper_path).arg(action).args(args).stdout(Stdio::piped()).spawn()
+    let helper_path = crate::paths::app_file(&append_app_ext("yaffe-helper"));
+    std::process::Command::new(helper_path)
+        .current_dir(crate::paths::app_dir())
+        .arg(action)
+        .args(args)
+        .stdout(Stdio::piped())
+        .spawn()
 }
 
 pub fn append_app_ext(path: &str) -> String {
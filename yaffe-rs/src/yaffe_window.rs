@@ -3,8 +3,9 @@ use crate::graphics::Graphics;
 use crate::input::Actions;
 use crate::job_system::JobResult;
 use crate::logger::LogEntry;
-use crate::modals::{display_error, display_modal_raw, ModalSize, RestrictedMode, ScraperModal};
-use crate::scraper::{GameScrapeResult, PlatformScrapeResult};
+use crate::modals::{display_error, display_modal_raw, MessageModal, ModalSize, RestrictedMode, ScraperModal};
+use crate::scraper::{GameScrapeResult, PlatformScrapeResult, ScrapeMode};
+use crate::state::GroupType;
 use crate::ui::{DeferredAction, WidgetTree};
 use crate::widgets::InfoPane;
 use crate::windowing::{WindowHandler, WindowHelper};
@@ -17,6 +18,13 @@ impl WindowHandler for WidgetTree<YaffeState> {
         //Check for any updates to the settings file
         // animations.process(self, delta_time);
         crate::settings::update_settings(&mut self.data.settings).log("Unable to retrieve updated settings");
+
+        //Pick up any roms that were added or removed while Yaffe is running
+        if !self.data.is_overlay_active() && self.data.rom_watcher.take_changes() {
+            let mut handler = DeferredAction::new();
+            crate::platform::sync_rom_files(&mut self.data, &mut handler);
+            handler.resolve(self);
+        }
         self.fixed_update(delta_time)
     }
 
@@ -26,7 +34,9 @@ impl WindowHandler for WidgetTree<YaffeState> {
         if !self.data.is_overlay_active() {
             //Update the platform and emulator list from database
             if self.data.refresh_list {
-                crate::platform::get_database_info(&mut self.data);
+                let mut handler = DeferredAction::new();
+                crate::platform::get_database_info(&mut self.data, &mut handler);
+                handler.resolve(self);
                 self.data.refresh_list = false;
             }
 
@@ -49,17 +59,30 @@ impl WindowHandler for WidgetTree<YaffeState> {
         match action {
             Actions::ShowMenu => {
                 if !self.is_modal_open() {
-                    let items = vec![
-                        "Scan For New Roms".to_string(),
+                    let mut items = vec!["Scan For New Roms".to_string(), "Clean Up Missing Roms".to_string()];
+                    let group = self.data.get_selected_group();
+                    if group.kind.is_game_list() && self.data.get_selected_tile().is_some() {
+                        items.push("Search Game Again".to_string());
+                        items.push("Add To Collection".to_string());
+                        if let GroupType::Collection(_) = group.kind {
+                            items.push("Remove From Collection".to_string());
+                        }
+                    }
+                    if let GroupType::Emulator = group.kind {
+                        items.push("Search Platform Games Again".to_string());
+                    }
+                    items.extend([
+                        "Collections".to_string(),
                         "Add Emulator".to_string(),
+                        "Switch Profile".to_string(),
                         match self.data.restricted_mode {
-                            RestrictedMode::On(_) => "Disable Restricted Mode".to_string(),
+                            RestrictedMode::On { .. } => "Disable Restricted Mode".to_string(),
                             RestrictedMode::Off => "Enable Restricted Mode".to_string(),
                         },
                         "Settings".to_string(),
                         "Exit Yaffe".to_string(),
                         "Shut Down".to_string(),
-                    ];
+                    ]);
 
                     let list = crate::modals::MenuModal::from(items);
                     crate::modals::display_modal_raw(self, "Menu", None, list, ModalSize::Third);
@@ -91,10 +114,19 @@ fn process_jobs(ui: &mut WidgetTree<YaffeState>, graphics: &mut Graphics, job_re
                 let asset_slot = crate::assets::get_asset_slot(&mut map, &key);
                 asset_slot.set_data(data, dimensions);
             }
-            JobResult::SearchGame(result) => match result {
+            JobResult::SearchGame(result, ScrapeMode::Refresh) => {
+                if let Some(summary) = crate::platform::apply_rescrape(&mut ui.data, result) {
+                    let content = MessageModal::from(&summary);
+                    display_modal_raw(ui, "Search Platform Games Again", None, content, ModalSize::Half);
+                }
+            }
+            JobResult::SearchGame(result, mode) => match result {
                 Ok(result) => {
-                    if let Some(game) = result.get_exact() {
-                        crate::platform::insert_game(&mut ui.data, &game.info, game.boxart.clone());
+                    let exact = if mode == ScrapeMode::Select { None } else { result.get_exact() };
+                    if let Some(game) = exact {
+                        if let Err(e) = crate::platform::insert_game(&mut ui.data, &game.info, game.boxart.clone()) {
+                            display_error(ui, format!("Unable to save game: {e}"));
+                        }
                     } else if result.count > 0 {
                         let items = result.results;
                         let content = ScraperModal::from(items, false, build_game_info);
@@ -105,6 +137,8 @@ fn process_jobs(ui: &mut WidgetTree<YaffeState>, graphics: &mut Graphics, job_re
                             content,
                             ModalSize::Half,
                         );
+                    } else if mode == ScrapeMode::Select {
+                        display_error(ui, format!("No results found for {}", result.request));
                     }
                 }
                 Err(e) => display_error(ui, format!("Error occured while searching games: {e:?}")),
@@ -112,7 +146,9 @@ fn process_jobs(ui: &mut WidgetTree<YaffeState>, graphics: &mut Graphics, job_re
             JobResult::SearchPlatform(result) => match result {
                 Ok(result) => {
                     if let Some(platform) = result.get_exact() {
-                        crate::platform::insert_platform(&mut ui.data, &platform.info);
+                        if let Err(e) = crate::platform::insert_platform(&mut ui.data, &platform.info) {
+                            display_error(ui, format!("Unable to save platform: {e}"));
+                        }
                     } else if result.count > 0 {
                         let items = result.results;
                         let content = ScraperModal::from(items, true, build_platform_info);
@@ -137,5 +173,5 @@ fn build_game_info(item: &GameScrapeResult) -> InfoPane<YaffeState> {
         ("Rating".to_string(), item.info.rating.clone()),
         ("Released".to_string(), item.info.released.clone()),
     ];
-    InfoPane::from(AssetKey::Url(item.boxart.clone()), item.info.overview.clone(), attributes)
+    InfoPane::from(item.boxart_key(), item.info.overview.clone(), attributes)
 }